@@ -21,10 +21,17 @@
 // SOFTWARE.
 
 use criterion::{criterion_group, criterion_main, Criterion};
-use euid::EUID;
+use euid::{Generator, OsRandom, SystemClock, EUID};
 
 pub fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function("create", |b| b.iter(|| EUID::create()));
+    c.bench_function("create", |b| b.iter(EUID::create));
+    // Before the entropy pool: one getrandom call per EUID.
+    let mut unbuffered = Generator::new(SystemClock, OsRandom);
+    c.bench_function("create_unbuffered", |b| b.iter(|| unbuffered.create()));
+
+    let euid: EUID = EUID::create().unwrap_or_default();
+    c.bench_function("next", |b| b.iter(|| euid.next()));
+    c.bench_function("next_unbuffered", |b| b.iter(|| unbuffered.next(&euid)));
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,111 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Write;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use euid::{EncodedEuid, EUID};
+
+/// Count heap allocations made by the whole process.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Fixed-size `fmt::Write` sink, so that formatting does not allocate by itself.
+struct Sink([u8; 64], usize);
+
+impl Write for Sink {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        let end: usize = self.1 + s.len();
+        self.0
+            .get_mut(self.1..end)
+            .ok_or(std::fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.1 = end;
+        Ok(())
+    }
+}
+
+fn assert_no_alloc<T>(name: &str, mut f: impl FnMut() -> T) {
+    let before: usize = ALLOCATIONS.load(Ordering::SeqCst);
+    for _ in 0..1000 {
+        black_box(f());
+    }
+    let allocations: usize = ALLOCATIONS.load(Ordering::SeqCst) - before;
+    assert_eq!(0, allocations, "{} allocated {} times", name, allocations);
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let euid: EUID = EUID::create().unwrap_or_default();
+    let encoded: EncodedEuid = euid.encoded(true);
+    let mut buf: [u8; 27] = [0u8; 27];
+
+    assert_no_alloc("encode_to", || euid.encode_to(&mut buf, true).len());
+    assert_no_alloc("encoded", || euid.encoded(true));
+    assert_no_alloc("display", || {
+        let mut sink: Sink = Sink([0u8; 64], 0);
+        write!(sink, "{}", euid).map(|_| sink.1)
+    });
+    assert_no_alloc("from_str", || EUID::from_str(&encoded));
+    assert_no_alloc("decode_bytes", || EUID::decode_bytes(encoded.as_bytes()));
+
+    c.bench_function("encode", |b| b.iter(|| euid.encode(true)));
+    c.bench_function("encode_to", |b| {
+        b.iter(|| euid.encode_to(&mut buf, true).len())
+    });
+    c.bench_function("encoded", |b| b.iter(|| euid.encoded(true)));
+    c.bench_function("display", |b| {
+        b.iter(|| {
+            let mut sink: Sink = Sink([0u8; 64], 0);
+            write!(sink, "{}", euid).map(|_| sink.1)
+        })
+    });
+    c.bench_function("from_str", |b| b.iter(|| EUID::from_str(&encoded)));
+    c.bench_function("decode_bytes", |b| {
+        b.iter(|| EUID::decode_bytes(encoded.as_bytes()))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
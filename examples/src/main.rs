@@ -37,7 +37,7 @@ fn help() {
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() == 0 || args.len() == 1 {
+    if args.len() <= 1 {
         help();
     } else if args.len() == 2 {
         if "create" == args[1] {
@@ -46,15 +46,15 @@ fn main() {
             help();
         }
     } else if args.len() == 3 {
-        let cmd = args[1].as_str().clone();
+        let cmd = args[1].as_str();
         match cmd {
             "create_with_extension" => {
-                let ext_str = args[2].as_str().clone();
-                let ext = u16::from_str_radix(ext_str, 10);
+                let ext_str = args[2].as_str();
+                let ext = ext_str.parse::<u16>();
                 match ext {
                     Ok(n) => {
                         if n > 32767 {
-                            println!("{}", "extension overflow");
+                            println!("extension overflow");
                         } else {
                             println!("{}", EUID::create_with_extension(n).unwrap_or_default());
                         }
@@ -65,14 +65,11 @@ fn main() {
                 }
             }
             "create_batch" => {
-                let n_str = args[2].as_str().clone();
-                let n = u16::from_str_radix(n_str, 10);
+                let n_str = args[2].as_str();
+                let n = n_str.parse::<u16>();
                 match n {
                     Ok(v) => {
-                        let mut euid: EUID = EUID::create().unwrap_or_default();
-                        println!("{}", euid);
-                        for _ in 1..v {
-                            euid = euid.next().unwrap_or_default();
+                        for euid in EUID::create_batch(v as usize).unwrap_or_default() {
                             println!("{}", euid);
                         }
                     }
@@ -82,7 +79,7 @@ fn main() {
                 }
             }
             "from_string" => {
-                let encoded = args[2].as_str().clone();
+                let encoded = args[2].as_str();
                 let euid = EUID::from_str(encoded);
                 match euid {
                     Ok(v) => {
@@ -94,8 +91,8 @@ fn main() {
                 }
             }
             "from" => {
-                let encoded = args[2].as_str().clone();
-                let n = u128::from_str_radix(encoded, 10);
+                let encoded = args[2].as_str();
+                let n = encoded.parse::<u128>();
                 match n {
                     Ok(euid) => {
                         let v: EUID = EUID::from(euid);
@@ -110,22 +107,20 @@ fn main() {
         };
     } else if args.len() == 4 {
         if "create_with_extension_batch" == args[1] {
-            let n_str = args[3].as_str().clone();
-            let n = u16::from_str_radix(n_str, 10);
+            let n_str = args[3].as_str();
+            let n = n_str.parse::<u16>();
             match n {
                 Ok(v) => {
-                    let ext_str = args[2].as_str().clone();
-                    let ext = u16::from_str_radix(ext_str, 10);
+                    let ext_str = args[2].as_str();
+                    let ext = ext_str.parse::<u16>();
                     match ext {
                         Ok(n) => {
                             if n > 32767 {
-                                println!("{}", "extension overflow");
+                                println!("extension overflow");
                             } else {
-                                let mut euid: EUID =
-                                    EUID::create_with_extension(n).unwrap_or_default();
-                                println!("{}", euid);
-                                for _ in 1..v {
-                                    euid = euid.next().unwrap_or_default();
+                                for euid in EUID::create_batch_with_extension(v as usize, n)
+                                    .unwrap_or_default()
+                                {
                                     println!("{}", euid);
                                 }
                             }
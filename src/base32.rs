@@ -23,18 +23,14 @@
 use crate::{Error, EUID};
 
 #[allow(non_camel_case_types)]
-type u5 = usize;
+type u5 = u8;
 
-static ENCODING_SYMBOLS: &[char] = &[
-    '0', '1', '2', '3', '4', '5', '6', '7', //
-    '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', //
-    'G', 'H', 'J', 'K', 'M', 'N', 'P', 'Q', //
-    'R', 'S', 'T', 'V', 'W', 'X', 'Y', 'Z', //
-];
+/// Crockford base32 symbols, in symbol value order.
+pub(crate) static ENCODING_SYMBOLS: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
 
-const MCP: usize = usize::MAX;
+const MCP: u5 = u8::MAX;
 
-static DECODING_SYMBOLS: &[u5] = &[
+const DECODING_SYMBOLS: &[u5] = &[
     MCP, MCP, MCP, MCP, MCP, MCP, MCP, MCP, // 0
     MCP, MCP, MCP, MCP, MCP, MCP, MCP, MCP, // 8
     MCP, MCP, MCP, MCP, MCP, MCP, MCP, MCP, // 16
@@ -55,7 +51,7 @@ static DECODING_SYMBOLS: &[u5] = &[
 
 #[cfg(not(feature = "non_binary"))]
 fn to_u5_slice(hi: u64, lo: u64) -> [u5; 27] {
-    let mut dst: [u5; 27] = [0usize; 27];
+    let mut dst: [u5; 27] = [0u8; 27];
     dst[0] = ((hi >> 59) & 0x1f) as u5;
     dst[1] = ((hi >> 54) & 0x1f) as u5;
     dst[2] = ((hi >> 49) & 0x1f) as u5;
@@ -87,7 +83,7 @@ fn to_u5_slice(hi: u64, lo: u64) -> [u5; 27] {
 }
 
 #[cfg(not(feature = "non_binary"))]
-fn to_u64_slice(slice: &[u5; 27]) -> (u64, u64) {
+const fn to_u64_slice(slice: &[u5; 27]) -> (u64, u64) {
     let hi: u64 = ((slice[0] as u64) << 59)
         | ((slice[1] as u64) << 54)
         | ((slice[2] as u64) << 49)
@@ -117,8 +113,13 @@ fn to_u64_slice(slice: &[u5; 27]) -> (u64, u64) {
     (hi, lo)
 }
 
+/// View the encoded symbols as `&str`, they are all ASCII.
+pub fn as_str(dst: &[u8; 27]) -> &str {
+    core::str::from_utf8(dst).unwrap_or_default()
+}
+
 #[cfg(feature = "non_binary")]
-pub fn encode(euid: &EUID, checkmod: bool) -> String {
+pub fn encode_to<'a>(euid: &EUID, checkmod: bool, dst: &'a mut [u8; 27]) -> &'a str {
     let check = (if checkmod {
         crate::check::m7(euid)
     } else {
@@ -129,116 +130,129 @@ pub fn encode(euid: &EUID, checkmod: bool) -> String {
     let mut p2 = ((euid.0 & 0x7ffff) << 1) | (euid.1 >> 63);
     let mut p3 = (((euid.1 as u128) & 0x7fffffffffffffff) << 7) | check;
 
-    let mut p1_str: [char; 9] = ['0'; 9];
-    let mut p2_str: [char; 4] = ['0'; 4];
-    let mut p3_str: [char; 14] = ['0'; 14];
+    let (p1_str, rest) = dst.split_at_mut(9);
+    let (p2_str, p3_str) = rest.split_at_mut(4);
 
-    for (_, c) in p1_str.iter_mut().enumerate() {
+    for c in p1_str.iter_mut().rev() {
         let m = p1 % 32;
         *c = ENCODING_SYMBOLS[m as usize];
         p1 = (p1 - m) / 32;
     }
-    for (_, c) in p2_str.iter_mut().enumerate() {
+    for c in p2_str.iter_mut().rev() {
         let m = p2 % 32;
         *c = ENCODING_SYMBOLS[m as usize];
         p2 = (p2 - m) / 32;
     }
-    for (_, c) in p3_str.iter_mut().enumerate() {
+    for c in p3_str.iter_mut().rev() {
         let mr = p3 % 32;
         *c = ENCODING_SYMBOLS[mr as usize];
         p3 = (p3 - mr) / 32;
     }
-    format!(
-        "{}{}{}",
-        p1_str.iter().rev().collect::<String>(),
-        p2_str.iter().rev().collect::<String>(),
-        p3_str.iter().rev().collect::<String>(),
-    )
+    as_str(dst)
 }
 
 #[cfg(not(feature = "non_binary"))]
-pub fn encode(euid: &EUID, checkmod: bool) -> String {
+pub fn encode_to<'a>(euid: &EUID, checkmod: bool, dst: &'a mut [u8; 27]) -> &'a str {
     let slice: [u5; 27] = to_u5_slice(euid.0, euid.1);
-    let mut dst: String = String::with_capacity(27);
-    dst.push(ENCODING_SYMBOLS[slice[0]]);
-    dst.push(ENCODING_SYMBOLS[slice[1]]);
-    dst.push(ENCODING_SYMBOLS[slice[2]]);
-    dst.push(ENCODING_SYMBOLS[slice[3]]);
-    dst.push(ENCODING_SYMBOLS[slice[4]]);
-    dst.push(ENCODING_SYMBOLS[slice[5]]);
-    dst.push(ENCODING_SYMBOLS[slice[6]]);
-    dst.push(ENCODING_SYMBOLS[slice[7]]);
-    dst.push(ENCODING_SYMBOLS[slice[8]]);
-    dst.push(ENCODING_SYMBOLS[slice[9]]);
-    dst.push(ENCODING_SYMBOLS[slice[10]]);
-    dst.push(ENCODING_SYMBOLS[slice[11]]);
-    dst.push(ENCODING_SYMBOLS[slice[12]]);
-    dst.push(ENCODING_SYMBOLS[slice[13]]);
-    dst.push(ENCODING_SYMBOLS[slice[14]]);
-    dst.push(ENCODING_SYMBOLS[slice[15]]);
-    dst.push(ENCODING_SYMBOLS[slice[16]]);
-    dst.push(ENCODING_SYMBOLS[slice[17]]);
-    dst.push(ENCODING_SYMBOLS[slice[18]]);
-    dst.push(ENCODING_SYMBOLS[slice[19]]);
-    dst.push(ENCODING_SYMBOLS[slice[20]]);
-    dst.push(ENCODING_SYMBOLS[slice[21]]);
-    dst.push(ENCODING_SYMBOLS[slice[22]]);
-    dst.push(ENCODING_SYMBOLS[slice[23]]);
-    dst.push(ENCODING_SYMBOLS[slice[24]]);
+    for (c, v) in dst.iter_mut().zip(slice.iter()).take(25) {
+        *c = ENCODING_SYMBOLS[*v as usize];
+    }
     let check: usize = if checkmod {
         crate::check::m7(euid)
     } else {
         0x7f
     };
-    dst.push(ENCODING_SYMBOLS[slice[25] | (check >> 5)]);
-    dst.push(ENCODING_SYMBOLS[check & 0x1f]);
-    dst
+    dst[25] = ENCODING_SYMBOLS[slice[25] as usize | (check >> 5)];
+    dst[26] = ENCODING_SYMBOLS[check & 0x1f];
+    as_str(dst)
 }
 
-#[cfg(feature = "non_binary")]
-pub fn decode(encoded: &str) -> Result<EUID, Error> {
+/// Returns true if `b` decodes to a symbol value.
+pub const fn is_symbol(b: u8) -> bool {
+    (b as usize) < DECODING_SYMBOLS.len() && DECODING_SYMBOLS[b as usize] != MCP
+}
+
+/// Returns the value of symbol `b`, None if `b` is not a symbol.
+pub const fn symbol_value(b: u8) -> Option<u8> {
+    if is_symbol(b) {
+        Some(DECODING_SYMBOLS[b as usize])
+    } else {
+        None
+    }
+}
+
+/// Returns true if the check symbols of `encoded` carry a check-mod, as opposed to the
+/// `0x7f` no check-mod marker. Bytes must be symbols, see [`is_symbol`].
+pub const fn has_checkmod(encoded: &[u8; 27]) -> bool {
+    let r: usize = (DECODING_SYMBOLS[encoded[25] as usize] & 0x3) as usize;
+    ((r << 5) | DECODING_SYMBOLS[encoded[26] as usize] as usize) != 0x7f
+}
+
+/// What decoding accepted beyond the canonical form, see [`EUID::decode_with_report`].
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DecodeReport {
+    /// A check-mod was present and verified, as opposed to the no check-mod marker.
+    pub checkmod: bool,
+    /// `O`, `I` or `L` were decoded as `0` or `1`.
+    pub aliases_normalized: bool,
+    /// Lowercase letters were decoded as uppercase.
+    pub lowercase_normalized: bool,
+}
+
+impl DecodeReport {
+    /// Returns true if the input was uppercase, without aliases and with a check-mod.
+    pub const fn is_canonical(&self) -> bool {
+        self.checkmod && !self.aliases_normalized && !self.lowercase_normalized
+    }
+}
+
+/// Returns true if `b` is the canonical spelling of its symbol.
+const fn is_canonical(b: u8) -> bool {
+    is_symbol(b) && ENCODING_SYMBOLS[DECODING_SYMBOLS[b as usize] as usize] == b
+}
+
+/// Map every byte of `encoded` to its symbol value.
+const fn to_symbols(encoded: &[u8]) -> Result<[u5; 27], Error> {
     if encoded.len() != 27 {
         return Err(Error::InvalidLength(encoded.len(), 27));
     }
+    let mut slice: [u5; 27] = [0u8; 27];
+    let mut i: usize = 0;
+    while i < 27 {
+        let b: u8 = encoded[i];
+        if !is_symbol(b) {
+            return Err(Error::InvalidCharacter(b as char, i));
+        }
+        slice[i] = DECODING_SYMBOLS[b as usize];
+        i += 1;
+    }
+    Ok(slice)
+}
+
+#[cfg(feature = "non_binary")]
+pub const fn decode_bytes(encoded: &[u8]) -> Result<EUID, Error> {
+    let slice: [u5; 27] = match to_symbols(encoded) {
+        Ok(slice) => slice,
+        Err(e) => return Err(e),
+    };
     let mut p1: u64 = 0;
     let mut p2: u64 = 0;
     let mut p3: u128 = 0;
 
-    let (value, rest) = encoded.split_at(9);
-    for (i, c) in value.chars().rev().enumerate() {
-        let code_point = c as usize;
-        if code_point > DECODING_SYMBOLS.len() {
-            return Err(Error::InvalidCharacter(c));
-        }
-        let v = DECODING_SYMBOLS[c as usize];
-        if v == usize::MAX {
-            return Err(Error::InvalidCharacter(c));
-        }
-        p1 += v as u64 * (32u64.pow(i as u32));
+    let mut i: usize = 0;
+    while i < 9 {
+        p1 += slice[8 - i] as u64 * (32u64.pow(i as u32));
+        i += 1;
     }
-    let (value, rest) = rest.split_at(4);
-    for (i, c) in value.chars().rev().enumerate() {
-        let code_point = c as usize;
-        if code_point > DECODING_SYMBOLS.len() {
-            return Err(Error::InvalidCharacter(c));
-        }
-        let v = DECODING_SYMBOLS[c as usize];
-        if v == usize::MAX {
-            return Err(Error::InvalidCharacter(c));
-        }
-        p2 += v as u64 * (32u64.pow(i as u32));
+    i = 0;
+    while i < 4 {
+        p2 += slice[12 - i] as u64 * (32u64.pow(i as u32));
+        i += 1;
     }
-    let (value, _) = rest.split_at(14);
-    for (i, c) in value.chars().rev().enumerate() {
-        let code_point = c as usize;
-        if code_point > DECODING_SYMBOLS.len() {
-            return Err(Error::InvalidCharacter(c));
-        }
-        let v = DECODING_SYMBOLS[c as usize];
-        if v == usize::MAX {
-            return Err(Error::InvalidCharacter(c));
-        }
-        p3 += v as u128 * (32u128.pow(i as u32));
+    i = 0;
+    while i < 14 {
+        p3 += slice[26 - i] as u128 * (32u128.pow(i as u32));
+        i += 1;
     }
     let check = (p3 & 0x7f) as usize;
 
@@ -258,25 +272,15 @@ pub fn decode(encoded: &str) -> Result<EUID, Error> {
 }
 
 #[cfg(not(feature = "non_binary"))]
-pub fn decode(encoded: &str) -> Result<EUID, Error> {
-    if encoded.len() != 27 {
-        return Err(Error::InvalidLength(encoded.len(), 27));
-    }
-    let mut slice: [u5; 27] = [0usize; 27];
-    for (i, c) in encoded.chars().enumerate() {
-        let code_point: usize = c as usize;
-        if code_point >= DECODING_SYMBOLS.len() {
-            return Err(Error::InvalidCharacter(c));
-        }
-        slice[i] = DECODING_SYMBOLS[code_point];
-        if slice[i] == usize::MAX {
-            return Err(Error::InvalidCharacter(c));
-        }
-    }
-    let r: usize = slice[25] & 0x3;
+pub const fn decode_bytes(encoded: &[u8]) -> Result<EUID, Error> {
+    let mut slice: [u5; 27] = match to_symbols(encoded) {
+        Ok(slice) => slice,
+        Err(e) => return Err(e),
+    };
+    let r: usize = (slice[25] & 0x3) as usize;
     slice[25] &= 0x1c;
     let e: (u64, u64) = to_u64_slice(&slice);
-    let check: usize = (r << 5) | slice[26];
+    let check: usize = (r << 5) | slice[26] as usize;
     if check == 0x7f {
         Ok(EUID(e.0, e.1))
     } else {
@@ -290,23 +294,65 @@ pub fn decode(encoded: &str) -> Result<EUID, Error> {
     }
 }
 
+pub fn decode(encoded: &str) -> Result<EUID, Error> {
+    match decode_bytes(encoded.as_bytes()) {
+        // The first invalid byte starts a character, report the whole character.
+        Err(Error::InvalidCharacter(_, offset)) => Err(Error::InvalidCharacter(
+            encoded[offset..].chars().next().unwrap_or_default(),
+            offset,
+        )),
+        result => result,
+    }
+}
+
+pub fn decode_with_report(encoded: &str) -> Result<(EUID, DecodeReport), Error> {
+    let euid: EUID = decode(encoded)?;
+    let mut report: DecodeReport = DecodeReport::default();
+    let mut bytes: [u8; 27] = [0u8; 27];
+    bytes.copy_from_slice(encoded.as_bytes());
+    report.checkmod = has_checkmod(&bytes);
+    for b in bytes.iter() {
+        report.lowercase_normalized |= b.is_ascii_lowercase();
+        report.aliases_normalized |= matches!(b.to_ascii_uppercase(), b'O' | b'I' | b'L');
+    }
+    Ok((euid, report))
+}
+
+pub fn decode_strict(encoded: &str) -> Result<EUID, Error> {
+    let euid: EUID = decode(encoded)?;
+    if let Some(offset) = encoded.bytes().position(|b| !is_canonical(b)) {
+        return Err(Error::NonCanonicalCharacter(
+            encoded.as_bytes()[offset] as char,
+            offset,
+        ));
+    }
+    let mut bytes: [u8; 27] = [0u8; 27];
+    bytes.copy_from_slice(encoded.as_bytes());
+    if !has_checkmod(&bytes) {
+        return Err(Error::MissingCheckmod);
+    }
+    Ok(euid)
+}
+
 #[cfg(test)]
 mod tests {
 
+    #[cfg(feature = "std")]
     use std::str::FromStr;
 
-    #[cfg(not(feature = "non_binary"))]
+    #[cfg(all(feature = "std", not(feature = "non_binary")))]
     #[test]
     fn convert_bits_test() {
         for _ in 0..65536 {
             let (hi, lo) = crate::random::random_u128();
-            let slice: [usize; 27] = super::to_u5_slice(hi, lo);
+            let slice: [u8; 27] = super::to_u5_slice(hi, lo);
             let (hi2, lo2) = super::to_u64_slice(&slice);
             assert_eq!(hi, hi2);
             assert_eq!(lo, lo2);
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn encode_test() {
         for i in 0..32767 {
@@ -315,6 +361,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn decode_test() {
         assert_eq!(
@@ -328,17 +375,25 @@ mod tests {
             super::decode("C8X2HA87098A0W837DX13FEAWVV")
         );
         assert_eq!(
-            Err(crate::Error::InvalidCharacter('U')),
+            Err(crate::Error::InvalidCharacter('U', 26)),
             super::decode("C8EE934SR007G5Q94QKKXFRFV8U")
         );
         assert_eq!(
-            Err(crate::Error::InvalidCharacter('}')),
+            Err(crate::Error::InvalidCharacter('}', 26)),
             super::decode("C8EE934SR007G5Q94QKKXFRFV8}")
         );
         assert_eq!(
-            Err(crate::Error::InvalidCharacter('@')),
+            Err(crate::Error::InvalidCharacter('@', 26)),
             super::decode("C8EE934SR007G5Q94QKKXFRFV8@")
         );
+        assert_eq!(
+            Err(crate::Error::InvalidCharacter('U', 0)),
+            super::decode("U8EE934SR007G5Q94QKKXFRFV8B")
+        );
+        assert_eq!(
+            Err(crate::Error::InvalidCharacter('é', 12)),
+            super::decode("C8EE934SR007é5Q94QKKXFRFV8")
+        );
         assert_eq!(
             Err(crate::Error::InvalidLength(26, 27)),
             super::decode("C8EE934SR007G5Q94QKKXFRFV8")
@@ -350,4 +405,75 @@ mod tests {
                 .to_string()
         );
     }
+
+    #[test]
+    fn decode_strict_test() {
+        use crate::{DecodeReport, Error, EUID};
+
+        let canonical: &str = "C8ZM14GR4JXG0MQXVY18S8TJNA6";
+        let euid: EUID = canonical.parse().unwrap();
+        let report = |checkmod, aliases_normalized, lowercase_normalized| DecodeReport {
+            checkmod,
+            aliases_normalized,
+            lowercase_normalized,
+        };
+        assert_eq!(
+            Ok((euid, report(true, false, false))),
+            super::decode_with_report(canonical)
+        );
+        assert_eq!(
+            Ok((euid, report(true, false, true))),
+            super::decode_with_report("c8zm14gr4jxg0mqxvy18s8tjna6")
+        );
+        assert_eq!(
+            Ok((euid, report(true, true, false))),
+            super::decode_with_report("C8ZM14GR4JXGOMQXVYL8S8TJNA6")
+        );
+        assert_eq!(
+            Ok((euid, report(false, false, false))),
+            super::decode_with_report("C8ZM14GR4JXG0MQXVY18S8TJNBZ")
+        );
+        assert_eq!(
+            Err(Error::InvalidCheckmod(71, 70)),
+            super::decode_with_report("C8ZM14GR4JXG0MQXVY18S8TJNA7")
+        );
+        assert!(report(true, false, false).is_canonical());
+        assert!(!report(false, false, false).is_canonical());
+
+        assert_eq!(Ok(euid), super::decode_strict(canonical));
+        assert_eq!(
+            Err(Error::NonCanonicalCharacter('m', 3)),
+            super::decode_strict("C8Zm14GR4JXG0MQXVY18S8TJNA6")
+        );
+        assert_eq!(
+            Err(Error::NonCanonicalCharacter('l', 18)),
+            super::decode_strict("C8ZM14GR4JXG0MQXVYl8S8TJNA6")
+        );
+        assert_eq!(
+            Err(Error::MissingCheckmod),
+            super::decode_strict("C8ZM14GR4JXG0MQXVY18S8TJNBZ")
+        );
+        assert_eq!(
+            Err(Error::InvalidLength(26, 27)),
+            super::decode_strict("C8ZM14GR4JXG0MQXVY18S8TJNA")
+        );
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let euid: EUID = EUID::from(rand::Rng::gen::<u128>(&mut rng));
+            let encoded = euid.encoded(true);
+            assert_eq!(Ok(euid), super::decode_strict(&encoded));
+            let lowercase: String = encoded.to_ascii_lowercase();
+            let (decoded, report) = super::decode_with_report(&lowercase).unwrap();
+            assert_eq!(euid, decoded);
+            assert!(report.checkmod && !report.is_canonical());
+            if lowercase.bytes().any(|b| b.is_ascii_lowercase()) {
+                assert!(super::decode_strict(&lowercase).is_err());
+            }
+            assert_eq!(
+                Err(Error::MissingCheckmod),
+                super::decode_strict(&euid.encoded(false))
+            );
+        }
+    }
 }
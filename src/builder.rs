@@ -0,0 +1,259 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{Error, EUID};
+
+/// Fields of an EUID, as returned by [`EUID::parts`].
+///
+/// Every EUID decomposes into parts and [`EUID::from_parts`] puts them back
+/// together bit for bit, including EUIDs that were not created by this crate.
+///
+/// ```text
+/// | timestamp (45) | filler (15 - ext_len) | extension (ext_len) | ext_len (4) | r_hi (32) | r_lo (32) |
+/// ```
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Parts {
+    /// Milliseconds since the epoch the EUID was created with (45 bits).
+    pub timestamp: u64,
+    /// Number of extension bits (0 if there is no extension, at most 15).
+    pub ext_len: u8,
+    /// User attached data (`ext_len` bits, 0 if there is no extension).
+    pub extension: u16,
+    /// Random bits between the timestamp and the extension (`15 - ext_len` bits).
+    pub filler: u16,
+    /// High part of the randomness, incremented by monotonic generation.
+    pub r_hi: u32,
+    /// Low part of the randomness.
+    pub r_lo: u32,
+}
+
+/// Build an EUID from explicit timestamp, extension and random bits.
+///
+/// Bits that are not set are zero. Unlike [`EUID::from_parts`], the extension
+/// length is derived from the extension, so the EUID is the one `create_with_extension`
+/// would have created with the same timestamp and randomness.
+///
+/// Example:
+/// ```rust
+/// use euid::{EuidBuilder, EUID};
+///
+/// let euid: EUID = EuidBuilder::new()
+///     .with_timestamp(1700000000000)
+///     .with_extension(7)
+///     .with_r_hi(1)
+///     .build()
+///     .unwrap();
+/// assert_eq!(1700000000000, euid.timestamp());
+/// assert_eq!(Some(7), euid.extension());
+/// assert_eq!(1, euid.parts().r_hi);
+/// ```
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct EuidBuilder {
+    timestamp: u64,
+    extension: Option<u16>,
+    filler: u16,
+    r_hi: u32,
+    r_lo: u32,
+}
+
+impl EuidBuilder {
+    /// Create builder for the EUID with all bits zero.
+    pub fn new() -> Self {
+        EuidBuilder::default()
+    }
+
+    /// Set the timestamp in milliseconds since the epoch (max 45 bits).
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Set the user attached data (max 15 bits).
+    pub fn with_extension(mut self, extension: u16) -> Self {
+        self.extension = Some(extension);
+        self
+    }
+
+    /// Set the random bits between the timestamp and the extension
+    /// (max 15 bits without extension, `15 - ext_len` bits with it).
+    pub fn with_filler(mut self, filler: u16) -> Self {
+        self.filler = filler;
+        self
+    }
+
+    /// Set the high part of the randomness.
+    pub fn with_r_hi(mut self, r_hi: u32) -> Self {
+        self.r_hi = r_hi;
+        self
+    }
+
+    /// Set the low part of the randomness.
+    pub fn with_r_lo(mut self, r_lo: u32) -> Self {
+        self.r_lo = r_lo;
+        self
+    }
+
+    /// Build the EUID.
+    /// Error returns if the timestamp is more than 45 bits, the extension is more than 15 bits
+    /// or the filler does not fit next to the extension.
+    pub fn build(&self) -> Result<EUID, Error> {
+        let (ext_len, extension) = match self.extension {
+            Some(ext) if ext as u64 > EUID::EXT_DATA_BITMASK => {
+                return Err(Error::ExtensionOverflow(ext))
+            }
+            Some(ext) => (EUID::get_ext_bit_len(ext) as u8, ext),
+            None => (0, 0),
+        };
+        EUID::from_parts(Parts {
+            timestamp: self.timestamp,
+            ext_len,
+            extension,
+            filler: self.filler,
+            r_hi: self.r_hi,
+            r_lo: self.r_lo,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{Error, EuidBuilder, Parts, EUID};
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn parts_test() {
+        for value in [
+            0u128,
+            u128::MAX,
+            1 << 127,
+            0x0123456789abcdef_fedcba9876543210,
+        ] {
+            let euid: EUID = EUID::from(value);
+            let parts: Parts = euid.parts();
+            assert_eq!(Ok(euid), EUID::from_parts(parts));
+        }
+        for i in 0..1000u16 {
+            let euid: EUID = EUID::create_with_extension(i % 0x8000).unwrap();
+            let parts: Parts = euid.parts();
+            assert_eq!(euid.timestamp(), parts.timestamp);
+            assert_eq!(euid.extension(), Some(parts.extension));
+            assert_eq!(Ok(euid), EUID::from_parts(parts));
+        }
+        let euid: EUID = EUID::create().unwrap();
+        assert_eq!(0, euid.parts().ext_len);
+        assert_eq!(0, euid.parts().extension);
+        assert_eq!(Ok(euid), EUID::from_parts(euid.parts()));
+    }
+
+    #[test]
+    fn from_parts_test() {
+        let parts: Parts = Parts {
+            timestamp: EUID::TIMESTAMP_BITMASK,
+            ext_len: 15,
+            extension: 0x7fff,
+            filler: 0,
+            r_hi: u32::MAX,
+            r_lo: u32::MAX,
+        };
+        assert_eq!(Ok(EUID::from(u128::MAX)), EUID::from_parts(parts));
+        assert_eq!(
+            Err(Error::TimestampOverflow(EUID::TIMESTAMP_BITMASK + 1)),
+            EUID::from_parts(Parts {
+                timestamp: EUID::TIMESTAMP_BITMASK + 1,
+                ..parts
+            })
+        );
+        assert_eq!(
+            Err(Error::ExtensionLengthOverflow(16)),
+            EUID::from_parts(Parts {
+                ext_len: 16,
+                ..parts
+            })
+        );
+        assert_eq!(
+            Err(Error::ExtensionOverflow(0x7fff)),
+            EUID::from_parts(Parts {
+                ext_len: 14,
+                ..parts
+            })
+        );
+        assert_eq!(
+            Err(Error::FillerOverflow(1)),
+            EUID::from_parts(Parts { filler: 1, ..parts })
+        );
+        assert_eq!(
+            Err(Error::ExtensionOverflow(1)),
+            EUID::from_parts(Parts {
+                ext_len: 0,
+                extension: 1,
+                filler: 0,
+                ..parts
+            })
+        );
+    }
+
+    #[test]
+    fn builder_test() {
+        let euid: EUID = EuidBuilder::new()
+            .with_timestamp(1700000000000)
+            .with_filler(0x7fff)
+            .with_r_hi(2)
+            .with_r_lo(3)
+            .build()
+            .unwrap();
+        let parts: Parts = euid.parts();
+        assert_eq!(1700000000000, parts.timestamp);
+        assert_eq!(None, euid.extension());
+        assert_eq!(0x7fff, parts.filler);
+        assert_eq!((2, 3), (parts.r_hi, parts.r_lo));
+
+        let euid: EUID = EuidBuilder::new()
+            .with_extension(0x10)
+            .with_filler(0x3ff)
+            .build()
+            .unwrap();
+        assert_eq!(Some(0x10), euid.extension());
+        assert_eq!(5, euid.parts().ext_len);
+        assert_eq!(0x3ff, euid.parts().filler);
+
+        assert_eq!(
+            Err(Error::FillerOverflow(0x400)),
+            EuidBuilder::new()
+                .with_extension(0x10)
+                .with_filler(0x400)
+                .build()
+        );
+        assert_eq!(
+            Err(Error::ExtensionOverflow(0x8000)),
+            EuidBuilder::new().with_extension(0x8000).build()
+        );
+        assert_eq!(
+            Err(Error::TimestampOverflow(u64::MAX)),
+            EuidBuilder::new().with_timestamp(u64::MAX).build()
+        );
+        // Extension 0 is one bit long, unlike no extension at all.
+        let euid: EUID = EuidBuilder::new().with_extension(0).build().unwrap();
+        assert_eq!(Some(0), euid.extension());
+        assert_eq!(1, euid.parts().ext_len);
+    }
+}
@@ -0,0 +1,163 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{EncodedEuid, Error, EUID};
+
+/// Rewrite an encoded EUID to its canonical form: uppercase, `0` and `1` instead of the
+/// `O`, `I`, `L` aliases, and a freshly computed check-mod symbol (or the no check-mod
+/// marker if `with_checkmod` is false). Accepts whatever `EUID::from_str` accepts, so a
+/// wrong check-mod is still an error.
+///
+/// Example:
+/// ```rust
+/// let canonical = euid::canonicalize("c8zm14gr4jxgomqxvyl8s8tjnbz", true).unwrap();
+/// assert_eq!("C8ZM14GR4JXG0MQXVY18S8TJNA6", canonical.as_str());
+/// ```
+pub fn canonicalize(encoded: &str, with_checkmod: bool) -> Result<EncodedEuid, Error> {
+    let euid: EUID = encoded.parse()?;
+    Ok(euid.encoded(with_checkmod))
+}
+
+/// Canonicalize every string of `encoded`, see [`canonicalize`].
+///
+/// Example:
+/// ```rust
+/// let stored = ["c8zm14gr4jxgomqxvyl8s8tjna6", "C8ZM14GR4JXG0MQXVY18S8TJNBZ", "oops"];
+/// let canonical: Vec<_> = euid::canonicalize_all(stored, true)
+///     .map(|result| result.map(|encoded| encoded.to_string()))
+///     .collect();
+/// assert_eq!(Ok("C8ZM14GR4JXG0MQXVY18S8TJNA6".to_string()), canonical[0]);
+/// assert_eq!(Ok("C8ZM14GR4JXG0MQXVY18S8TJNA6".to_string()), canonical[1]);
+/// assert_eq!(Err(euid::Error::InvalidLength(4, 27)), canonical[2]);
+/// ```
+pub fn canonicalize_all<I>(encoded: I, with_checkmod: bool) -> Canonicalize<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    Canonicalize {
+        iter: encoded.into_iter(),
+        with_checkmod,
+    }
+}
+
+/// Iterator returned by [`canonicalize_all`].
+#[derive(Clone, Debug)]
+pub struct Canonicalize<I> {
+    iter: I,
+    with_checkmod: bool,
+}
+
+impl<I> Iterator for Canonicalize<I>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    type Item = Result<EncodedEuid, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let encoded = self.iter.next()?;
+        Some(canonicalize(encoded.as_ref(), self.with_checkmod))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{EncodedEuid, Error, EUID};
+    use rand::Rng;
+
+    /// Misspell `encoded` the ways `EUID::from_str` accepts: lowercase and aliases.
+    fn misspell(encoded: &str, rng: &mut impl Rng) -> String {
+        encoded
+            .chars()
+            .map(|c| match (c, rng.gen_range(0..4)) {
+                ('0', 0) => 'O',
+                ('0', 1) => 'o',
+                ('1', 0) => 'I',
+                ('1', 1) => 'l',
+                ('1', 2) => 'L',
+                (c, 3) => c.to_ascii_lowercase(),
+                (c, _) => c,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn canonicalize_test() {
+        assert_eq!(
+            "C8ZM14GR4JXG0MQXVY18S8TJNBZ",
+            crate::canonicalize("C8ZM14GR4JXG0MQXVY18S8TJNA6", false)
+                .unwrap()
+                .as_str()
+        );
+        assert_eq!(
+            Err(Error::InvalidCheckmod(71, 70)),
+            crate::canonicalize("C8ZM14GR4JXG0MQXVY18S8TJNA7", true)
+        );
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let euid: EUID = EUID::from(rng.gen::<u128>());
+            let checkmod: bool = rng.gen();
+            let misspelled: String = misspell(&euid.encoded(rng.gen()), &mut rng);
+            let canonical: EncodedEuid = crate::canonicalize(&misspelled, checkmod).unwrap();
+            assert_eq!(euid.encoded(checkmod), canonical);
+        }
+    }
+
+    #[test]
+    fn canonicalize_all_test() {
+        let mut rng = rand::thread_rng();
+        let euids: Vec<EUID> = (0..100).map(|_| EUID::from(rng.gen::<u128>())).collect();
+        let stored: Vec<String> = euids
+            .iter()
+            .map(|euid| misspell(&euid.encoded(true), &mut rng))
+            .collect();
+        let canonical: Vec<EncodedEuid> = crate::canonicalize_all(&stored, true)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(100, canonical.len());
+        for (euid, encoded) in euids.iter().zip(canonical.iter()) {
+            assert_eq!(Ok(*euid), EUID::decode_strict(encoded));
+        }
+        // Canonical text sorts like the EUIDs themselves.
+        let mut sorted: Vec<EncodedEuid> = canonical.clone();
+        sorted.sort();
+        let mut expected: Vec<EUID> = euids.clone();
+        expected.sort();
+        assert_eq!(
+            expected,
+            sorted
+                .iter()
+                .map(|encoded| encoded.parse().unwrap())
+                .collect::<Vec<EUID>>()
+        );
+        assert_eq!(
+            (3, Some(3)),
+            crate::canonicalize_all(["", "", ""], false).size_hint()
+        );
+    }
+}
@@ -25,7 +25,7 @@ use crate::EUID;
 // reference implementation for language that doesn't support 128 bit integer natively.
 #[cfg(feature = "euid_64")]
 #[allow(dead_code)]
-fn shift_right7(v: (u64, u64)) -> (u64, u64) {
+const fn shift_right7(v: (u64, u64)) -> (u64, u64) {
     let mask: u64 = 0x7f;
     let a1: u64 = v.0 & mask;
     let a: u64 = v.0 >> 7;
@@ -36,7 +36,7 @@ fn shift_right7(v: (u64, u64)) -> (u64, u64) {
 // reference implementation for language that doesn't support 128 bit integer natively.
 #[cfg(feature = "euid_64")]
 #[allow(dead_code)]
-fn add_u128(a: (u64, u64), b: (u64, u64)) -> (u64, u64) {
+const fn add_u128(a: (u64, u64), b: (u64, u64)) -> (u64, u64) {
     let mut a1 = a.0;
     let mut a2 = a.1;
     let mut b1 = b.0;
@@ -71,7 +71,7 @@ fn sub_u128(a: (u64, u64), b: (u64, u64)) -> (u64, u64) {
 // reference implementation for language that doesn't support 128 bit integer natively.
 #[cfg(feature = "euid_64")]
 #[allow(dead_code)]
-fn is_gt_p(v: (u64, u64), p: u64) -> bool {
+const fn is_gt_p(v: (u64, u64), p: u64) -> bool {
     if v.0 != 0 {
         true
     } else {
@@ -82,7 +82,7 @@ fn is_gt_p(v: (u64, u64), p: u64) -> bool {
 // reference implementation for language that doesn't support 128 bit integer natively.
 #[cfg(feature = "euid_64")]
 #[allow(dead_code)]
-pub fn m7(euid: &EUID) -> usize {
+pub const fn m7(euid: &EUID) -> usize {
     let p: u64 = 0x7f;
     let mut i = add_u128((0, euid.1 & p), shift_right7((euid.0, euid.1)));
     while is_gt_p(i, p) {
@@ -95,9 +95,9 @@ pub fn m7(euid: &EUID) -> usize {
     }
 }
 
-#[cfg(all(feature = "std", not(feature = "euid_64")))]
+#[cfg(not(feature = "euid_64"))]
 #[allow(dead_code)]
-pub fn m7(euid: &EUID) -> usize {
+pub const fn m7(euid: &EUID) -> usize {
     let p: u128 = 0x7f;
     let n = ((euid.0 as u128) << 64) | (euid.1 as u128);
     let mut i = (n & p) + (n >> 7);
@@ -118,13 +118,17 @@ mod tests {
         ((hi as u128) << 64) | (lo as u128)
     }
 
+    fn random_u128() -> (u64, u64) {
+        (rand::random(), rand::random())
+    }
+
     #[cfg(feature = "euid_64")]
     #[test]
     fn add_u128_test() {
         for _ in 0..65535 {
-            let (_, lo_a) = crate::random::random_u128();
-            let (hi_b, lo_b) = crate::random::random_u128();
-            let hi_a: u64 = crate::random::random_u32() as u64;
+            let (_, lo_a) = random_u128();
+            let (hi_b, lo_b) = random_u128();
+            let hi_a: u64 = rand::random::<u32>() as u64;
 
             let a: u128 = to_u128(hi_a, lo_a);
             let b: u128 = to_u128(hi_b, lo_b);
@@ -139,9 +143,9 @@ mod tests {
     #[test]
     fn sub_u128_test() {
         for _ in 0..65535 {
-            let (hi_a, lo_a) = crate::random::random_u128();
-            let (_, lo_b) = crate::random::random_u128();
-            let hi_b: u64 = crate::random::random_u32() as u64;
+            let (hi_a, lo_a) = random_u128();
+            let (_, lo_b) = random_u128();
+            let hi_b: u64 = rand::random::<u32>() as u64;
 
             let a: u128 = to_u128(hi_a, lo_a);
             let b: u128 = to_u128(hi_b, lo_b);
@@ -156,7 +160,7 @@ mod tests {
     #[test]
     fn shift_right_test() {
         for _ in 0..65535 {
-            let (hi, lo) = crate::random::random_u128();
+            let (hi, lo) = random_u128();
             let n: u128 = to_u128(hi, lo);
             let (l, r) = crate::check::shift_right7((hi, lo));
             assert_eq!(n >> 7, to_u128(l, r));
@@ -167,18 +171,18 @@ mod tests {
     #[test]
     fn divmod_test() {
         for _ in 0..65535 {
-            let (hi, lo) = crate::random::random_u128();
+            let (hi, lo) = random_u128();
             let n = (to_u128(hi, lo) % 127) as usize;
             let code = super::m7(&crate::EUID(hi, lo));
             assert_eq!(n, code);
         }
     }
 
-    #[cfg(all(feature = "std", not(feature = "euid_64")))]
+    #[cfg(not(feature = "euid_64"))]
     #[test]
     fn divmod_test() {
         for _ in 0..65535 {
-            let (hi, lo) = crate::random::random_u128();
+            let (hi, lo) = random_u128();
             let n = (to_u128(hi, lo) % 127) as usize;
             let code = super::m7(&crate::EUID(hi, lo));
             assert_eq!(n, code);
@@ -0,0 +1,167 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::siphash::{key_halves, siphash24, wipe};
+use crate::EUID;
+
+/// Number of Feistel rounds.
+const ROUNDS: u8 = 8;
+
+/// Keyed permutation of the 128-bit EUID space, to hide the creation time (and any
+/// extension) of EUIDs shown outside.
+///
+/// A balanced Feistel network of 8 rounds over the two 64-bit halves, with SipHash-2-4
+/// keyed by the cipher key as round function. Any 128-bit value is a valid EUID, so the
+/// output is an EUID too: it encodes and decodes like any other, but its timestamp and
+/// extension are meaningless and it does not sort by time. Only holders of the key can
+/// map it back with [`EuidCipher::decrypt`]. A cipher can not be cloned, share one by
+/// reference or `Arc`; its key is overwritten on drop.
+///
+/// Example:
+/// ```rust
+/// use euid::{EuidCipher, EUID};
+///
+/// let cipher: EuidCipher = EuidCipher::new(*b"0123456789abcdef");
+/// let internal: EUID = EUID::create().unwrap();
+/// let public: String = cipher.encrypt(&internal).encode(true);
+/// assert_eq!(internal, cipher.decrypt(&public.parse().unwrap()));
+/// ```
+pub struct EuidCipher {
+    key: (u64, u64),
+}
+
+impl EuidCipher {
+    /// Create cipher with a 128-bit key.
+    pub fn new(key: [u8; 16]) -> Self {
+        EuidCipher {
+            key: key_halves(&key),
+        }
+    }
+
+    fn round(&self, round: u8, half: u64) -> u64 {
+        let mut message: [u8; 9] = [0u8; 9];
+        message[..8].copy_from_slice(&half.to_le_bytes());
+        message[8] = round;
+        siphash24(self.key.0, self.key.1, &message)
+    }
+
+    /// Map `euid` to its opaque form.
+    pub fn encrypt(&self, euid: &EUID) -> EUID {
+        let (mut left, mut right) = (euid.0, euid.1);
+        for round in 0..ROUNDS {
+            let next: u64 = left ^ self.round(round, right);
+            left = right;
+            right = next;
+        }
+        EUID(left, right)
+    }
+
+    /// Map an opaque EUID back, the inverse of [`EuidCipher::encrypt`].
+    pub fn decrypt(&self, euid: &EUID) -> EUID {
+        let (mut left, mut right) = (euid.0, euid.1);
+        for round in (0..ROUNDS).rev() {
+            let previous: u64 = right ^ self.round(round, left);
+            right = left;
+            left = previous;
+        }
+        EUID(left, right)
+    }
+}
+
+impl Drop for EuidCipher {
+    fn drop(&mut self) {
+        wipe(&mut self.key);
+    }
+}
+
+impl core::fmt::Debug for EuidCipher {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("EuidCipher(..)")
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+
+    use crate::{EuidCipher, EUID};
+    use rand::Rng;
+    use std::collections::HashSet;
+
+    const KEY: [u8; 16] = *b"0123456789abcdef";
+
+    #[test]
+    fn round_trip_test() {
+        let cipher: EuidCipher = EuidCipher::new(KEY);
+        let mut rng = rand::thread_rng();
+        for _ in 0..10000 {
+            let euid: EUID = EUID::from(rng.gen::<u128>());
+            let encrypted: EUID = cipher.encrypt(&euid);
+            assert_ne!(euid, encrypted);
+            assert_eq!(euid, cipher.decrypt(&encrypted));
+            assert_eq!(euid, cipher.encrypt(&cipher.decrypt(&euid)));
+            let decoded: EUID = encrypted.encode(true).parse().unwrap();
+            assert_eq!(euid, cipher.decrypt(&decoded));
+        }
+        for euid in [EUID::default(), EUID(u64::MAX, u64::MAX)] {
+            assert_eq!(euid, cipher.decrypt(&cipher.encrypt(&euid)));
+        }
+        assert_eq!("EuidCipher(..)", format!("{:?}", cipher));
+    }
+
+    #[test]
+    fn distinctness_test() {
+        let cipher: EuidCipher = EuidCipher::new(KEY);
+        let other: EuidCipher = EuidCipher::new(*b"0123456789abcdeF");
+        // EUIDs of one millisecond differ in their low bits only.
+        let base: EUID = EUID::create().unwrap();
+        let euids: Vec<EUID> = (0..100000u64)
+            .map(|i| EUID(base.0, base.1.wrapping_add(i)))
+            .collect();
+        let encrypted: Vec<EUID> = euids.iter().map(|euid| cipher.encrypt(euid)).collect();
+        assert_eq!(euids.len(), encrypted.iter().collect::<HashSet<_>>().len());
+        let timestamps: HashSet<u64> = encrypted.iter().map(EUID::timestamp).collect();
+        assert!(timestamps.len() > 99000);
+        assert!(!encrypted.windows(2).all(|w| w[0] < w[1]));
+        for euid in euids.iter().take(1000) {
+            assert_ne!(cipher.encrypt(euid), other.encrypt(euid));
+        }
+        // Flipping one input bit flips about half of the output bits.
+        let mut rng = rand::thread_rng();
+        let mut flipped: u32 = 0;
+        for _ in 0..1000 {
+            let a: u128 = rng.gen();
+            let b: u128 = a ^ (1 << rng.gen_range(0..128));
+            let diff: u128 = u128::from(cipher.encrypt(&EUID::from(a)))
+                ^ u128::from(cipher.encrypt(&EUID::from(b)));
+            flipped += diff.count_ones();
+        }
+        assert!(flipped > 60 * 1000 && flipped < 68 * 1000);
+    }
+
+    #[test]
+    fn drop_test() {
+        let mut cipher = core::mem::ManuallyDrop::new(EuidCipher::new(*b"0123456789abcdef"));
+        assert_ne!((0, 0), cipher.key);
+        unsafe { core::mem::ManuallyDrop::drop(&mut cipher) };
+        assert_eq!((0, 0), cipher.key);
+    }
+}
@@ -0,0 +1,350 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{base32, EUID};
+use alloc::vec::Vec;
+
+/// Pairs of symbols commonly confused when EUIDs are read aloud, handwritten or retyped.
+/// `O`/`0` and `I`/`L`/`1` are decoded as the same symbol and need no correction.
+static CONFUSIONS: &[(u8, u8)] = &[
+    (b'U', b'V'),
+    (b'5', b'S'),
+    (b'8', b'B'),
+    (b'2', b'Z'),
+    (b'6', b'G'),
+    (b'0', b'D'),
+    (b'0', b'Q'),
+    (b'1', b'7'),
+    (b'M', b'N'),
+];
+
+/// Single edit that turns the input into a [`Correction`], declared from the most to the
+/// least likely. Positions are character indices in the input.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Edit {
+    /// Commonly confused symbol replaced, e.g. `U` by `V` or `5` by `S`.
+    Confusion {
+        position: usize,
+        from: char,
+        to: char,
+    },
+    /// Symbols at `position` and `position + 1` swapped.
+    Transposition { position: usize },
+    /// Symbol replaced.
+    Substitution {
+        position: usize,
+        from: char,
+        to: char,
+    },
+    /// Missing `symbol` inserted at `position`.
+    Insertion { position: usize, symbol: char },
+    /// Extra `symbol` at `position` deleted.
+    Deletion { position: usize, symbol: char },
+}
+
+/// Candidate returned by [`EUID::suggest_corrections`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Correction {
+    /// EUID the corrected input decodes to, its check-mod verified.
+    pub euid: EUID,
+    /// Edit applied to the input.
+    pub edit: Edit,
+}
+
+fn is_confusion(a: u8, b: u8) -> bool {
+    CONFUSIONS
+        .iter()
+        .any(|&(x, y)| (x == a && y == b) || (x == b && y == a))
+}
+
+/// Decode `encoded`, only if it carries a check-mod and the check-mod verifies.
+fn verify(encoded: &[u8; 27]) -> Option<EUID> {
+    match base32::decode_bytes(encoded) {
+        Ok(euid) if base32::has_checkmod(encoded) => Some(euid),
+        _ => None,
+    }
+}
+
+pub fn suggest_corrections(input: &str) -> Vec<Correction> {
+    let mut chars: [char; 28] = ['\0'; 28];
+    let mut n: usize = 0;
+    for c in input.chars() {
+        if n == chars.len() {
+            return Vec::new();
+        }
+        chars[n] = c;
+        n += 1;
+    }
+    // Non ASCII characters are never symbols, NUL stands in for them.
+    let mut bytes: [u8; 28] = [0u8; 28];
+    for (b, c) in bytes.iter_mut().zip(chars.iter()) {
+        if c.is_ascii() {
+            *b = c.to_ascii_uppercase() as u8;
+        }
+    }
+    let mut corrections: Vec<Correction> = Vec::new();
+    let mut candidate: [u8; 27] = [0u8; 27];
+    let mut push = |candidate: &[u8; 27], edit: Edit| {
+        if let Some(euid) = verify(candidate) {
+            corrections.push(Correction { euid, edit });
+        }
+    };
+    match n {
+        27 => {
+            candidate.copy_from_slice(&bytes[..27]);
+            if verify(&candidate).is_some() {
+                return Vec::new();
+            }
+            for position in 0..27 {
+                let from: u8 = bytes[position];
+                for &to in base32::ENCODING_SYMBOLS.iter().filter(|&&to| to != from) {
+                    candidate[position] = to;
+                    let (from, to) = (chars[position], to as char);
+                    if is_confusion(from.to_ascii_uppercase() as u8, to as u8) {
+                        push(&candidate, Edit::Confusion { position, from, to });
+                    } else {
+                        push(&candidate, Edit::Substitution { position, from, to });
+                    }
+                }
+                candidate[position] = from;
+            }
+            for position in 0..26 {
+                if bytes[position] != bytes[position + 1] {
+                    candidate.swap(position, position + 1);
+                    push(&candidate, Edit::Transposition { position });
+                    candidate.swap(position, position + 1);
+                }
+            }
+        }
+        26 => {
+            for position in 0..27 {
+                candidate[..position].copy_from_slice(&bytes[..position]);
+                candidate[position + 1..].copy_from_slice(&bytes[position..26]);
+                for &symbol in base32::ENCODING_SYMBOLS.iter() {
+                    candidate[position] = symbol;
+                    let symbol: char = symbol as char;
+                    push(&candidate, Edit::Insertion { position, symbol });
+                }
+            }
+        }
+        28 => {
+            for position in 0..28 {
+                candidate[..position].copy_from_slice(&bytes[..position]);
+                candidate[position..].copy_from_slice(&bytes[position + 1..]);
+                let symbol: char = chars[position];
+                push(&candidate, Edit::Deletion { position, symbol });
+            }
+        }
+        _ => (),
+    }
+    corrections.sort_by_key(|correction| correction.edit);
+    // Keep the most likely edit of those leading to the same EUID, e.g. inserting a
+    // symbol before or after the same symbol.
+    let mut unique: Vec<Correction> = Vec::with_capacity(corrections.len());
+    for correction in corrections {
+        if unique.iter().all(|c| c.euid != correction.euid) {
+            unique.push(correction);
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{Correction, Edit, EUID};
+    use rand::Rng;
+
+    const CANONICAL: &str = "C8ZM14GR4JXG0MQXVY18S8TJNA6";
+
+    fn expected() -> EUID {
+        CANONICAL.parse().unwrap()
+    }
+
+    fn random_encoded(rng: &mut impl Rng) -> [u8; 27] {
+        let euid: EUID = EUID::from(rng.gen::<u128>());
+        let mut buf: [u8; 27] = [0u8; 27];
+        euid.encode_to(&mut buf, true);
+        buf
+    }
+
+    /// Returns true if `typo` is rejected, or only decodes through the no check-mod marker.
+    /// Panics if that happens outside the check symbols (from the 24th symbol on).
+    fn is_detected(typo: &[u8], first: usize) -> bool {
+        match EUID::decode_bytes(typo) {
+            Err(_) => true,
+            Ok(_) => {
+                let typo: &[u8; 27] = core::convert::TryInto::try_into(typo).unwrap();
+                let marker: bool = !crate::base32::has_checkmod(typo);
+                assert!(!marker || first >= 23);
+                marker
+            }
+        }
+    }
+
+    #[test]
+    fn suggest_corrections_test() {
+        assert!(EUID::suggest_corrections(CANONICAL).is_empty());
+        assert!(EUID::suggest_corrections("C8ZM14GR4JXG0MQ").is_empty());
+
+        // U is not a symbol, V is the most likely intent.
+        let corrections: Vec<Correction> = EUID::suggest_corrections("C8ZM14GR4JXG0MQXUY18S8TJNA6");
+        assert_eq!(
+            Correction {
+                euid: expected(),
+                edit: Edit::Confusion {
+                    position: 16,
+                    from: 'U',
+                    to: 'V'
+                }
+            },
+            corrections[0]
+        );
+        assert!(corrections.len() < 32);
+
+        let corrections: Vec<Correction> = EUID::suggest_corrections("c8zm14gr4jxg0mqxvy1bs8tjna6");
+        assert_eq!(
+            Edit::Confusion {
+                position: 19,
+                from: 'b',
+                to: '8'
+            },
+            corrections[0].edit
+        );
+        assert_eq!(expected(), corrections[0].euid);
+
+        let corrections: Vec<Correction> = EUID::suggest_corrections("C8ZM14GR4JXG0MQXVY18S8JTNA6");
+        assert_eq!(Edit::Transposition { position: 22 }, corrections[0].edit);
+        assert_eq!(expected(), corrections[0].euid);
+
+        let corrections: Vec<Correction> = EUID::suggest_corrections("C8ZM14GR4JXG0MQXVY18S8TJN6");
+        assert!(corrections.iter().any(|c| c.euid == expected()
+            && c.edit
+                == Edit::Insertion {
+                    position: 25,
+                    symbol: 'A'
+                }));
+
+        let corrections: Vec<Correction> =
+            EUID::suggest_corrections("C8ZM14GR4JXG0MQXXVY18S8TJNA6");
+        assert!(corrections.iter().any(|c| c.euid == expected()
+            && c.edit
+                == Edit::Deletion {
+                    position: 15,
+                    symbol: 'X'
+                }));
+        // Deleting either X gives the same EUID, it is suggested once.
+        assert_eq!(
+            1,
+            corrections.iter().filter(|c| c.euid == expected()).count()
+        );
+    }
+
+    #[test]
+    fn suggest_corrections_random_test() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..256 {
+            let encoded: [u8; 27] = random_encoded(&mut rng);
+            let euid: EUID = EUID::decode_bytes(&encoded).unwrap();
+            let position: usize = rng.gen_range(0..27);
+            let mut typo: [u8; 27] = encoded;
+            typo[position] = crate::base32::ENCODING_SYMBOLS[rng.gen_range(0..32)];
+            if typo == encoded {
+                continue;
+            }
+            let input: &str = core::str::from_utf8(&typo).unwrap();
+            let corrections: Vec<Correction> = EUID::suggest_corrections(input);
+            assert!(corrections.iter().any(|c| c.euid == euid));
+            assert!(corrections.windows(2).all(|w| w[0].edit <= w[1].edit));
+        }
+    }
+
+    /// Detection capability of the mod 127 check-mod. Every single edit is detected, a
+    /// typo in the last symbols may however turn the check symbols into the no check-mod
+    /// marker (`0x7f`), which is accepted unless check-mod is required.
+    ///
+    /// | error class                          | undetected, verified check-mod | undetected, no check-mod marker |
+    /// |--------------------------------------|--------------------------------|---------------------------------|
+    /// | single substitution                  | none                           | rare, last two symbols          |
+    /// | adjacent transposition (`ab` → `ba`) | none                           | rare, last three symbols        |
+    /// | jump transposition (`abc` → `cba`)   | none                           | rare, last four symbols         |
+    /// | twin substitution (`aa` → `bb`)      | none                           | rare, last three symbols        |
+    /// | single insertion or deletion         | none, the length changes       | none                            |
+    /// | two substitutions                    | about 1 in 95                  | rare                            |
+    #[test]
+    fn detection_capability_test() {
+        let mut rng = rand::thread_rng();
+        let symbols: &[u8; 32] = crate::base32::ENCODING_SYMBOLS;
+        let (mut double, mut undetected): (usize, usize) = (0, 0);
+        for _ in 0..64 {
+            let encoded: [u8; 27] = random_encoded(&mut rng);
+            for i in 0..27 {
+                for &s in symbols.iter().filter(|&&s| s != encoded[i]) {
+                    let mut typo: [u8; 27] = encoded;
+                    typo[i] = s;
+                    assert!(is_detected(&typo, i));
+                }
+            }
+            for i in 0..26 {
+                let mut typo: [u8; 27] = encoded;
+                typo.swap(i, i + 1);
+                assert!(typo == encoded || is_detected(&typo, i));
+            }
+            for i in 0..25 {
+                let mut typo: [u8; 27] = encoded;
+                typo.swap(i, i + 2);
+                assert!(typo == encoded || is_detected(&typo, i));
+            }
+            for i in (0..26).filter(|&i| encoded[i] == encoded[i + 1]) {
+                for &s in symbols.iter().filter(|&&s| s != encoded[i]) {
+                    let mut typo: [u8; 27] = encoded;
+                    typo[i] = s;
+                    typo[i + 1] = s;
+                    assert!(is_detected(&typo, i));
+                }
+            }
+            for i in 0..27 {
+                let mut shorter: Vec<u8> = encoded.to_vec();
+                shorter.remove(i);
+                assert!(is_detected(&shorter, 0));
+                let mut longer: Vec<u8> = encoded.to_vec();
+                longer.insert(i, symbols[rng.gen_range(0..32)]);
+                assert!(is_detected(&longer, 0));
+            }
+            for _ in 0..4096 {
+                let (i, j) = (rng.gen_range(0..26), rng.gen_range(0..26));
+                let mut typo: [u8; 27] = encoded;
+                typo[i] = symbols[rng.gen_range(0..32)];
+                typo[j] = symbols[rng.gen_range(0..32)];
+                if i == j || typo[i] == encoded[i] || typo[j] == encoded[j] {
+                    continue;
+                }
+                double += 1;
+                if EUID::decode_bytes(&typo).is_ok() && crate::base32::has_checkmod(&typo) {
+                    undetected += 1;
+                }
+            }
+        }
+        // About 1 in 95, symbol differences are small compared to the modulus.
+        assert!(undetected * 80 < double && double < undetected * 115);
+    }
+}
@@ -0,0 +1,120 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{base32, EUID};
+
+/// Encoded EUID kept on the stack, as returned by [`EUID::encoded`].
+///
+/// Derefs to `str`, so it can be used wherever a string slice is expected
+/// without allocating.
+///
+/// Example:
+/// ```rust
+/// use euid::{EncodedEuid, EUID};
+///
+/// let euid: EUID = EUID::create().unwrap_or_default();
+/// let encoded: EncodedEuid = euid.encoded(true);
+/// assert_eq!(27, encoded.len());
+/// assert_eq!(euid.to_string(), &*encoded);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EncodedEuid([u8; 27]);
+
+impl EncodedEuid {
+    /// Encode `euid`, with or without check-mod.
+    pub fn new(euid: &EUID, checkmod: bool) -> EncodedEuid {
+        let mut buf: [u8; 27] = [0u8; 27];
+        base32::encode_to(euid, checkmod, &mut buf);
+        EncodedEuid(buf)
+    }
+
+    /// Returns the encoded EUID as string slice.
+    pub fn as_str(&self) -> &str {
+        base32::as_str(&self.0)
+    }
+
+    /// Returns the encoded EUID as ASCII bytes.
+    pub fn as_bytes(&self) -> &[u8; 27] {
+        &self.0
+    }
+}
+
+impl From<EUID> for EncodedEuid {
+    /// Encode with check-mod.
+    fn from(euid: EUID) -> Self {
+        EncodedEuid::new(&euid, true)
+    }
+}
+
+impl core::ops::Deref for EncodedEuid {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for EncodedEuid {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for EncodedEuid {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl core::fmt::Display for EncodedEuid {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl core::fmt::Debug for EncodedEuid {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+
+    use crate::{EncodedEuid, EUID};
+
+    #[test]
+    fn encoded_test() {
+        for i in 0..1000u16 {
+            let euid: EUID = EUID::create_with_extension(i).unwrap();
+            let encoded: EncodedEuid = euid.encoded(true);
+            assert_eq!(euid.encode(true), encoded.as_str());
+            assert_eq!(euid.encode(false), &*euid.encoded(false));
+            assert_eq!(EncodedEuid::from(euid), encoded);
+            assert_eq!(Ok(euid), EUID::decode_bytes(encoded.as_bytes()));
+            assert_eq!(format!("{:?}", euid.encode(true)), format!("{:?}", encoded));
+        }
+        let a: EUID = EUID::create().unwrap();
+        let b: EUID = a.next().unwrap();
+        assert!(a.encoded(true) < b.encoded(true));
+    }
+}
@@ -0,0 +1,308 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{base32, Error, EUID};
+
+/// Letter case of encoded EUIDs.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Case {
+    /// `C8ZM14GR4JXG0MQXVY18S8TJNBZ` (default, canonical).
+    #[default]
+    Upper,
+    /// `c8zm14gr4jxg0mqxvy18s8tjnbz`.
+    Lower,
+}
+
+/// How to format an EUID as text, and which grouped form to accept when parsing it back.
+///
+/// The default is the canonical form: uppercase, with check-mod and without separators.
+///
+/// Example:
+/// ```rust
+/// use euid::{Case, EncodeOptions, EUID};
+///
+/// const GROUPED: EncodeOptions = EncodeOptions::new().with_group_size(5);
+///
+/// let euid: EUID = "C8ZM14GR4JXG0MQXVY18S8TJNBZ".parse().unwrap();
+/// let mut buf: [u8; EncodeOptions::MAX_LEN] = [0u8; EncodeOptions::MAX_LEN];
+/// assert_eq!("C8ZM1-4GR4J-XG0MQ-XVY18-S8TJN-A6", GROUPED.encode_to(&euid, &mut buf));
+/// assert_eq!(Ok(euid), GROUPED.decode("C8ZM1-4GR4J-XG0MQ-XVY18-S8TJN-A6"));
+///
+/// let lower: EncodeOptions = GROUPED.with_case(Case::Lower).with_separator(' ');
+/// assert_eq!("c8zm1 4gr4j xg0mq xvy18 s8tjn a6", lower.encode_to(&euid, &mut buf));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EncodeOptions {
+    case: Case,
+    checkmod: bool,
+    separator: u8,
+    group_size: usize,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        EncodeOptions::new()
+    }
+}
+
+impl EncodeOptions {
+    /// Length of the longest encoded form (groups of one symbol).
+    pub const MAX_LEN: usize = 27 + 26;
+
+    /// Create options for the canonical form.
+    pub const fn new() -> Self {
+        EncodeOptions {
+            case: Case::Upper,
+            checkmod: true,
+            separator: b'-',
+            group_size: 0,
+        }
+    }
+
+    /// Set the letter case of the output (parsing is case-insensitive).
+    pub const fn with_case(mut self, case: Case) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Set whether to encode the check-mod (default: true).
+    pub const fn with_checkmod(mut self, checkmod: bool) -> Self {
+        self.checkmod = checkmod;
+        self
+    }
+
+    /// Set the separator between groups (default: `-`).
+    ///
+    /// Panics if the separator is not ASCII or is a base32 symbol or alias, which would
+    /// make grouped EUIDs ambiguous.
+    pub const fn with_separator(mut self, separator: char) -> Self {
+        assert!(
+            separator.is_ascii() && !base32::is_symbol(separator as u8),
+            "separator must be ASCII and not a base32 symbol"
+        );
+        self.separator = separator as u8;
+        self
+    }
+
+    /// Split the output in groups of `group_size` symbols, 0 disables grouping (default).
+    pub const fn with_group_size(mut self, group_size: usize) -> Self {
+        self.group_size = group_size;
+        self
+    }
+
+    /// Returns the letter case of the output.
+    pub const fn case(&self) -> Case {
+        self.case
+    }
+
+    /// Returns whether the check-mod is encoded.
+    pub const fn checkmod(&self) -> bool {
+        self.checkmod
+    }
+
+    /// Returns the separator between groups.
+    pub const fn separator(&self) -> char {
+        self.separator as char
+    }
+
+    /// Returns the number of symbols per group, 0 if grouping is disabled.
+    pub const fn group_size(&self) -> usize {
+        self.group_size
+    }
+
+    /// Returns the length of EUIDs encoded with these options.
+    pub const fn encoded_len(&self) -> usize {
+        match (27 - 1usize).checked_div(self.group_size) {
+            Some(separators) => 27 + separators,
+            None => 27,
+        }
+    }
+
+    fn is_separator_at(&self, symbols: usize) -> bool {
+        symbols != 0 && symbols.checked_rem(self.group_size) == Some(0)
+    }
+
+    /// Encode `euid` into `buf` without allocating, returns the encoded part of `buf`.
+    pub fn encode_to<'a>(&self, euid: &EUID, buf: &'a mut [u8; EncodeOptions::MAX_LEN]) -> &'a str {
+        let mut symbols: [u8; 27] = [0u8; 27];
+        base32::encode_to(euid, self.checkmod, &mut symbols);
+        let mut len: usize = 0;
+        for (i, symbol) in symbols.iter().enumerate() {
+            if self.is_separator_at(i) {
+                buf[len] = self.separator;
+                len += 1;
+            }
+            buf[len] = match self.case {
+                Case::Upper => *symbol,
+                Case::Lower => symbol.to_ascii_lowercase(),
+            };
+            len += 1;
+        }
+        // Symbols and separator are ASCII.
+        core::str::from_utf8(&buf[..len]).unwrap_or_default()
+    }
+
+    /// Encode `euid` to string.
+    #[cfg(feature = "alloc")]
+    pub fn encode(&self, euid: &EUID) -> alloc::string::String {
+        let mut buf: [u8; EncodeOptions::MAX_LEN] = [0u8; EncodeOptions::MAX_LEN];
+        alloc::string::String::from(self.encode_to(euid, &mut buf))
+    }
+
+    /// Decode EUID encoded with these options.
+    ///
+    /// With grouping enabled, the separator must be exactly between the groups.
+    /// Case and check-mod are handled like `EUID::from_str` does: any case is accepted,
+    /// and so is a missing check-mod. Offsets in errors are byte offsets in `encoded`.
+    pub fn decode(&self, encoded: &str) -> Result<EUID, Error> {
+        if self.group_size == 0 {
+            return base32::decode(encoded);
+        }
+        if encoded.len() != self.encoded_len() {
+            return Err(Error::InvalidLength(encoded.len(), self.encoded_len()));
+        }
+        let mut symbols: [u8; 27] = [0u8; 27];
+        let mut n: usize = 0;
+        let mut expect_separator: bool = false;
+        for (offset, c) in encoded.char_indices() {
+            if expect_separator {
+                if c as u32 != self.separator as u32 {
+                    return Err(Error::InvalidCharacter(c, offset));
+                }
+                expect_separator = false;
+                continue;
+            }
+            if !c.is_ascii() || !base32::is_symbol(c as u8) {
+                return Err(Error::InvalidCharacter(c, offset));
+            }
+            symbols[n] = c as u8;
+            n += 1;
+            expect_separator = n < 27 && self.is_separator_at(n);
+        }
+        base32::decode_bytes(&symbols)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+
+    use rand::Rng;
+    use std::str::FromStr;
+
+    use crate::{base32, Case, EncodeOptions, Error, EUID};
+
+    #[test]
+    fn encode_options_test() {
+        let euid: EUID = EUID::from_str("C8ZM14GR4JXG0MQXVY18S8TJNBZ").unwrap();
+        let grouped: EncodeOptions = EncodeOptions::new().with_group_size(5);
+        assert_eq!(euid.encode(true), EncodeOptions::new().encode(&euid));
+        assert_eq!(
+            euid.encode(false),
+            EncodeOptions::new().with_checkmod(false).encode(&euid)
+        );
+        assert_eq!("C8ZM1-4GR4J-XG0MQ-XVY18-S8TJN-A6", grouped.encode(&euid));
+        assert_eq!(
+            "C8ZM1-4GR4J-XG0MQ-XVY18-S8TJN-BZ",
+            grouped.with_checkmod(false).encode(&euid)
+        );
+        assert_eq!(32, grouped.encoded_len());
+        assert_eq!(
+            "c8zm14gr4j_xg0mqxvy18_s8tjna6",
+            EncodeOptions::new()
+                .with_case(Case::Lower)
+                .with_separator('_')
+                .with_group_size(10)
+                .encode(&euid)
+        );
+        assert_eq!(
+            EncodeOptions::MAX_LEN,
+            EncodeOptions::new().with_group_size(1).encoded_len()
+        );
+        assert_eq!(27, EncodeOptions::new().with_group_size(27).encoded_len());
+
+        // FromStr keeps accepting only the canonical form.
+        assert_eq!(
+            Err(Error::InvalidLength(32, 27)),
+            EUID::from_str("C8ZM1-4GR4J-XG0MQ-XVY18-S8TJN-BZ")
+        );
+        assert_eq!(
+            Err(Error::InvalidCharacter('_', 5)),
+            grouped.decode("C8ZM1_4GR4J-XG0MQ-XVY18-S8TJN-BZ")
+        );
+        assert_eq!(
+            Err(Error::InvalidCharacter('-', 4)),
+            grouped.decode("C8ZM-14GR4J-XG0MQ-XVY18-S8TJN-BZ")
+        );
+        assert_eq!(
+            Err(Error::InvalidCharacter('U', 13)),
+            grouped.decode("C8ZM1-4GR4J-XU0MQ-XVY18-S8TJN-BZ")
+        );
+        assert_eq!(
+            Err(Error::InvalidCharacter('é', 6)),
+            grouped.decode("C8ZM1-é4GR4J-XG0MQ-XVY18-S8TJN-")
+        );
+        assert_eq!(
+            Err(Error::InvalidLength(27, 32)),
+            grouped.decode("C8ZM14GR4JXG0MQXVY18S8TJNBZ")
+        );
+    }
+
+    #[test]
+    fn encode_options_round_trip_test() {
+        let mut rng = rand::thread_rng();
+        let separators: Vec<char> = (0..128u8)
+            .filter(|&b| !base32::is_symbol(b))
+            .map(char::from)
+            .collect();
+        for b in (0..=255u8).filter(|&b| !separators.contains(&char::from(b))) {
+            assert!(
+                std::panic::catch_unwind(|| EncodeOptions::new().with_separator(char::from(b)))
+                    .is_err()
+            );
+        }
+        assert!(std::panic::catch_unwind(|| EncodeOptions::new().with_separator('é')).is_err());
+        for _ in 0..65536 {
+            let euid: EUID = EUID::from(rng.gen::<u128>());
+            let options: EncodeOptions = EncodeOptions::new()
+                .with_case(if rng.gen::<bool>() {
+                    Case::Upper
+                } else {
+                    Case::Lower
+                })
+                .with_checkmod(rng.gen::<bool>())
+                .with_separator(separators[rng.gen_range(0..separators.len())])
+                .with_group_size(rng.gen_range(0..30));
+            let encoded: String = options.encode(&euid);
+            assert_eq!(options.encoded_len(), encoded.len());
+            assert_eq!(
+                Ok(euid),
+                options.decode(&encoded),
+                "{:?} {}",
+                options,
+                encoded
+            );
+            if options.group_size() == 0 || options.group_size() >= 27 {
+                assert_eq!(Ok(euid), EUID::from_str(&encoded));
+            }
+        }
+    }
+}
@@ -0,0 +1,1681 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+#[cfg(feature = "std")]
+use portable_atomic::{AtomicU128, AtomicU64, Ordering};
+
+use crate::{Clock, Epoch, Error, Granularity, RandomSource, SeededRandom, EUID};
+#[cfg(feature = "std")]
+use crate::{PooledRandom, SystemClock};
+
+/// What monotonic generation does when the clock is behind the last created EUID,
+/// for example after an NTP step or a VM migration.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ClockRegression {
+    /// Keep the last timestamp and keep incrementing the randomness (default).
+    #[default]
+    Hold,
+    /// Block the calling thread until the clock catches up with the last timestamp
+    /// (busy-waiting without the `std` feature).
+    Wait,
+    /// Fail with [`Error::ClockRegression`].
+    Error,
+}
+
+/// What monotonic generation does when the randomness of the last created EUID
+/// can not be incremented any more within the same millisecond.
+///
+/// The async variants await a caller supplied sleep instead of spinning or sleeping.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CounterOverflow {
+    /// Fail with [`Error::CounterOverflow`] (default).
+    #[default]
+    Error,
+    /// Borrow the next millisecond as a logical clock. Until the clock catches up,
+    /// the borrowed timestamp is kept regardless of the [`ClockRegression`] policy.
+    Borrow,
+    /// Busy-wait until the clock ticks.
+    Spin,
+    /// Sleep until the clock ticks (busy-wait without the `std` feature).
+    Sleep,
+}
+
+/// Settings shared by [`Generator`] and [`EuidGenerator`].
+#[derive(Default, Copy, Clone, Debug)]
+struct Config {
+    epoch: Epoch,
+    clock_regression: ClockRegression,
+    counter_overflow: CounterOverflow,
+    granularity: Granularity,
+}
+
+impl Config {
+    /// Read `clock` as milliseconds since the epoch, truncated to the granularity.
+    fn now<C: Clock + ?Sized>(&self, clock: &C) -> Result<u64, Error> {
+        let now: u64 = clock.current_timestamp();
+        if now < self.epoch.unix_millis() {
+            Err(Error::BeforeEpoch(now))
+        } else {
+            Ok(self.granularity.truncate(now - self.epoch.unix_millis()))
+        }
+    }
+
+    /// Returns the bucket an EUID created at `timestamp` belongs to.
+    fn bucket(&self, timestamp: u64) -> u64 {
+        self.granularity.truncate(timestamp)
+    }
+
+    /// Returns the timestamp to store for an EUID created in `bucket`: the bucket start
+    /// plus a random offset within the bucket.
+    fn spread<R: RandomSource + ?Sized>(&self, bucket: u64, random: &mut R) -> Result<u64, Error> {
+        match self.granularity {
+            Granularity::Millisecond => Ok(bucket),
+            granularity => {
+                // Widening multiply maps a full 64-bit draw onto the bucket without modulo bias.
+                let r: u64 = random.try_random_u128()?.0;
+                Ok(bucket + ((u128::from(r) * u128::from(granularity.millis())) >> 64) as u64)
+            }
+        }
+    }
+
+    /// Read `clock` for an EUID that must not sort before an EUID created at `last`.
+    /// Timestamps up to `borrowed` were taken ahead of the clock and are not regressions.
+    ///
+    /// Returns the timestamp to use (or the policy error) and the size of the
+    /// regression in milliseconds, if the clock was behind `last`.
+    fn read_clock<C: Clock + ?Sized>(
+        &self,
+        clock: &C,
+        last: u64,
+        borrowed: u64,
+    ) -> (Result<u64, Error>, Option<u64>) {
+        let mut now: u64 = match self.now(clock) {
+            Ok(now) => now,
+            Err(e) => return (Err(e), None),
+        };
+        if now >= last {
+            return (Ok(now), None);
+        }
+        if last <= borrowed {
+            return (Ok(last), None);
+        }
+        let regression: u64 = last - now;
+        match self.clock_regression {
+            ClockRegression::Hold => (Ok(last), Some(regression)),
+            ClockRegression::Wait => {
+                while now < last {
+                    sleep(last - now);
+                    now = match self.now(clock) {
+                        Ok(now) => now,
+                        Err(e) => return (Err(e), Some(regression)),
+                    };
+                }
+                (Ok(now), Some(regression))
+            }
+            ClockRegression::Error => (Err(Error::ClockRegression(regression)), Some(regression)),
+        }
+    }
+
+    /// Returns how long until `clock` reaches the next bucket.
+    fn until_next_bucket<C: Clock + ?Sized>(&self, clock: &C) -> core::time::Duration {
+        let now: u64 = clock
+            .current_timestamp()
+            .saturating_sub(self.epoch.unix_millis());
+        let millis: u64 = self.granularity.millis();
+        core::time::Duration::from_millis(millis - now % millis)
+    }
+
+    /// Block until `clock` is past the bucket `timestamp` and returns the new bucket,
+    /// sleeping between reads if `policy` is [`CounterOverflow::Sleep`] or spinning otherwise.
+    fn wait_for_tick<C: Clock + ?Sized>(
+        &self,
+        clock: &C,
+        timestamp: u64,
+        policy: CounterOverflow,
+    ) -> Result<u64, Error> {
+        loop {
+            let now: u64 = self.now(clock)?;
+            if now > timestamp {
+                return Ok(now);
+            }
+            if policy == CounterOverflow::Sleep {
+                sleep(1);
+            } else {
+                core::hint::spin_loop();
+            }
+        }
+    }
+}
+
+/// Sleep the calling thread for `millis` milliseconds, or spin once without `std`.
+fn sleep(millis: u64) {
+    #[cfg(feature = "std")]
+    std::thread::sleep(std::time::Duration::from_millis(millis));
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = millis;
+        core::hint::spin_loop();
+    }
+}
+
+/// EUID generator with pluggable clock and random source.
+///
+/// `EUID::create`, `EUID::create_with_extension` and `EUID::next` behave like a
+/// `Generator` built from [`SystemClock`] and [`PooledRandom`].
+///
+/// Example:
+/// ```rust
+/// use euid::{Clock, Generator, OsRandom, EUID};
+///
+/// struct FixedClock(u64);
+///
+/// impl Clock for FixedClock {
+///     fn current_timestamp(&self) -> u64 {
+///         self.0
+///     }
+/// }
+///
+/// let mut generator = Generator::new(FixedClock(1700000000000), OsRandom);
+/// let euid: EUID = generator.create().unwrap_or_default();
+/// assert_eq!(1700000000000, euid.timestamp());
+/// ```
+#[cfg(feature = "std")]
+#[derive(Default, Clone, Debug)]
+pub struct Generator<C = SystemClock, R = PooledRandom> {
+    clock: C,
+    random: R,
+    config: Config,
+    last_clock_regression: Option<u64>,
+    borrowed: u64,
+}
+
+/// EUID generator with pluggable clock and random source.
+#[cfg(not(feature = "std"))]
+#[derive(Default, Clone, Debug)]
+pub struct Generator<C, R> {
+    clock: C,
+    random: R,
+    config: Config,
+    last_clock_regression: Option<u64>,
+    borrowed: u64,
+}
+
+impl<C: Clock> Generator<C, SeededRandom> {
+    /// Create generator whose randomness is [`SeededRandom`] seeded with `seed`.
+    ///
+    /// The same seed and the same clock readings give the same sequence of EUIDs on
+    /// every platform, which makes EUIDs usable in snapshot tests and golden files.
+    /// **Not for production identifiers:** the EUIDs are predictable from the seed.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::{Generator, ManualClock, EUID};
+    ///
+    /// let mut a = Generator::seeded(ManualClock::new(1700000000000), 42);
+    /// let mut b = Generator::seeded(ManualClock::new(1700000000000), 42);
+    /// assert_eq!(a.create(), b.create());
+    /// ```
+    pub fn seeded(clock: C, seed: u64) -> Self {
+        Generator::new(clock, SeededRandom::new(seed))
+    }
+}
+
+impl<C: Clock, R: RandomSource> Generator<C, R> {
+    /// Create generator from the given clock and random source.
+    pub fn new(clock: C, random: R) -> Self {
+        Generator {
+            clock,
+            random,
+            config: Config::default(),
+            last_clock_regression: None,
+            borrowed: 0,
+        }
+    }
+
+    /// Count timestamps from `epoch` instead of the Unix epoch.
+    ///
+    /// The epoch is not stored in the EUID: EUIDs created with different epochs do not
+    /// sort or compare meaningfully, and their timestamps must be read back with
+    /// [`EUID::unix_timestamp`] using the same epoch.
+    pub fn with_epoch(mut self, epoch: Epoch) -> Self {
+        self.config.epoch = epoch;
+        self
+    }
+
+    /// Set what `next` does when the clock is behind the given EUID.
+    pub fn with_clock_regression(mut self, policy: ClockRegression) -> Self {
+        self.config.clock_regression = policy;
+        self
+    }
+
+    /// Set what `next` does when the randomness of the given EUID can not be incremented.
+    pub fn with_counter_overflow(mut self, policy: CounterOverflow) -> Self {
+        self.config.counter_overflow = policy;
+        self
+    }
+
+    /// Store the creation time with `granularity` instead of the exact millisecond.
+    ///
+    /// `next` increments the randomness within the bucket of the given EUID, so derived
+    /// EUIDs stay monotonic. [`CounterOverflow::Borrow`] borrows the next bucket, and
+    /// [`CounterOverflow::Spin`] and [`CounterOverflow::Sleep`] wait for it.
+    pub fn with_granularity(mut self, granularity: Granularity) -> Self {
+        self.config.granularity = granularity;
+        self
+    }
+
+    /// Returns the clock used by this generator.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Returns the random source used by this generator.
+    pub fn random_source(&mut self) -> &mut R {
+        &mut self.random
+    }
+
+    /// Returns the clock regression policy of this generator.
+    pub fn clock_regression(&self) -> ClockRegression {
+        self.config.clock_regression
+    }
+
+    /// Returns the counter overflow policy of this generator.
+    pub fn counter_overflow(&self) -> CounterOverflow {
+        self.config.counter_overflow
+    }
+
+    /// Returns the epoch the timestamps of this generator count from.
+    pub fn epoch(&self) -> Epoch {
+        self.config.epoch
+    }
+
+    /// Returns the granularity of the creation time of this generator.
+    pub fn granularity(&self) -> Granularity {
+        self.config.granularity
+    }
+
+    /// Returns the size in milliseconds of the most recent clock regression
+    /// observed by `next`, or None if the clock never moved backwards.
+    pub fn last_clock_regression(&self) -> Option<u64> {
+        self.last_clock_regression
+    }
+
+    /// Create random EUID.
+    /// None will returns if the clock is before the epoch or more than 45 bits of milliseconds
+    /// after it (Friday, December 12, 3084 12:41:28.831 PM (UTC) for the Unix epoch).
+    pub fn create(&mut self) -> Option<EUID> {
+        self.try_create().ok()
+    }
+
+    /// Create random EUID, returning the reason of the failure.
+    pub fn try_create(&mut self) -> Result<EUID, Error> {
+        self.create_at_now(None)
+    }
+
+    /// Create random EUID with attachable data (max 15 bit).
+    /// None will returns if the clock is before the epoch or more than 45 bits of milliseconds
+    /// after it, or the extenstion (user attached data) is more then 15 bits.
+    pub fn create_with_extension(&mut self, extension: u16) -> Option<EUID> {
+        self.try_create_with_extension(extension).ok()
+    }
+
+    /// Create random EUID with attachable data (max 15 bit), returning the reason of the failure.
+    pub fn try_create_with_extension(&mut self, extension: u16) -> Result<EUID, Error> {
+        self.create_at_now(Some(extension))
+    }
+
+    /// Create `n` EUIDs in strictly ascending order.
+    ///
+    /// Within the batch, the randomness is incremented like `next` does; when it overflows,
+    /// the next millisecond is borrowed (see [`CounterOverflow::Borrow`]) regardless of the
+    /// counter overflow policy, so a batch never fails or blocks because of its size.
+    /// None will returns if the first EUID can not be created (see `create`) or the clock
+    /// regression policy is [`ClockRegression::Error`] and the clock moved backwards.
+    #[cfg(feature = "alloc")]
+    pub fn create_batch(&mut self, n: usize) -> Option<alloc::vec::Vec<EUID>> {
+        self.try_create_batch(n).ok()
+    }
+
+    /// Create `n` EUIDs in strictly ascending order, returning the reason of the failure.
+    #[cfg(feature = "alloc")]
+    pub fn try_create_batch(&mut self, n: usize) -> Result<alloc::vec::Vec<EUID>, Error> {
+        let mut euids: alloc::vec::Vec<EUID> = alloc::vec![EUID::default(); n];
+        self.fill_with(&mut euids, None)?;
+        Ok(euids)
+    }
+
+    /// Create `n` EUIDs in strictly ascending order, all with the same extension.
+    /// See `create_batch` and `create_with_extension`.
+    #[cfg(feature = "alloc")]
+    pub fn create_batch_with_extension(
+        &mut self,
+        n: usize,
+        extension: u16,
+    ) -> Option<alloc::vec::Vec<EUID>> {
+        self.try_create_batch_with_extension(n, extension).ok()
+    }
+
+    /// Create `n` EUIDs in strictly ascending order, all with the same extension,
+    /// returning the reason of the failure.
+    #[cfg(feature = "alloc")]
+    pub fn try_create_batch_with_extension(
+        &mut self,
+        n: usize,
+        extension: u16,
+    ) -> Result<alloc::vec::Vec<EUID>, Error> {
+        let mut euids: alloc::vec::Vec<EUID> = alloc::vec![EUID::default(); n];
+        self.fill_with(&mut euids, Some(extension))?;
+        Ok(euids)
+    }
+
+    /// Overwrite `euids` with EUIDs in strictly ascending order, like `create_batch`.
+    pub fn fill(&mut self, euids: &mut [EUID]) -> Result<(), Error> {
+        self.fill_with(euids, None)
+    }
+
+    /// Overwrite `euids` with EUIDs in strictly ascending order, all with the same extension,
+    /// like `create_batch_with_extension`.
+    pub fn fill_with_extension(&mut self, euids: &mut [EUID], extension: u16) -> Result<(), Error> {
+        self.fill_with(euids, Some(extension))
+    }
+
+    /// Turn this generator into an infinite iterator of strictly ascending EUIDs,
+    /// all with the same extension. See [`EuidIter`].
+    pub fn into_iter_with_extension(self, extension: u16) -> EuidIter<C, R> {
+        EuidIter {
+            generator: self,
+            extension: Some(extension),
+            last: None,
+        }
+    }
+
+    /// Derive monotonic EUID from `euid`.
+    ///
+    /// This function generate sortable EUID, None returns if overflow happens when incrementing randomness
+    /// and the counter overflow policy is [`CounterOverflow::Error`], or the clock regression policy is
+    /// [`ClockRegression::Error`] and the clock is behind `euid`.
+    pub fn next(&mut self, euid: &EUID) -> Option<EUID> {
+        self.try_next(euid).ok()
+    }
+
+    /// Derive monotonic EUID from `euid`, returning the reason of the failure.
+    pub fn try_next(&mut self, euid: &EUID) -> Result<EUID, Error> {
+        self.next_with(euid, self.config.counter_overflow)
+    }
+
+    /// Derive monotonic EUID from `euid` without blocking the thread.
+    ///
+    /// With [`CounterOverflow::Spin`] or [`CounterOverflow::Sleep`], an overflow awaits
+    /// `sleep(duration)` until the clock ticks, where `sleep` returns a future completing
+    /// after `duration`, e.g. the timer of the executor (`tokio::time::sleep`).
+    pub async fn next_async<F: core::future::Future<Output = ()>>(
+        &mut self,
+        euid: &EUID,
+        sleep: impl FnMut(core::time::Duration) -> F,
+    ) -> Option<EUID> {
+        self.try_next_async(euid, sleep).await.ok()
+    }
+
+    /// Derive monotonic EUID from `euid` without blocking the thread,
+    /// returning the reason of the failure, see [`Generator::next_async`].
+    pub async fn try_next_async<F: core::future::Future<Output = ()>>(
+        &mut self,
+        euid: &EUID,
+        mut sleep: impl FnMut(core::time::Duration) -> F,
+    ) -> Result<EUID, Error> {
+        let policy: CounterOverflow = match self.config.counter_overflow {
+            CounterOverflow::Spin | CounterOverflow::Sleep => CounterOverflow::Error,
+            policy => policy,
+        };
+        loop {
+            match self.next_with(euid, policy) {
+                Err(Error::CounterOverflow) if policy != self.config.counter_overflow => {
+                    sleep(self.config.until_next_bucket(&self.clock)).await
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn create_at_now(&mut self, extension: Option<u16>) -> Result<EUID, Error> {
+        let bucket: u64 = self.config.now(&self.clock)?;
+        let timestamp: u64 = self.config.spread(bucket, &mut self.random)?;
+        match extension {
+            Some(ext) => {
+                EUID::create_with_timestamp_and_extension_from(timestamp, ext, &mut self.random)
+            }
+            None => EUID::create_with_timestamp_from(timestamp, &mut self.random),
+        }
+    }
+
+    /// Create the EUID following `last` in a batch, or the first one if there is no `last`.
+    fn create_after(&mut self, last: Option<&EUID>, extension: Option<u16>) -> Result<EUID, Error> {
+        match last {
+            Some(last) => self.next_with(last, CounterOverflow::Borrow),
+            None => self.create_at_now(extension),
+        }
+    }
+
+    fn fill_with(&mut self, euids: &mut [EUID], extension: Option<u16>) -> Result<(), Error> {
+        let mut last: Option<EUID> = None;
+        for slot in euids.iter_mut() {
+            let euid: EUID = self.create_after(last.as_ref(), extension)?;
+            *slot = euid;
+            last = Some(euid);
+        }
+        Ok(())
+    }
+
+    fn next_with(&mut self, euid: &EUID, policy: CounterOverflow) -> Result<EUID, Error> {
+        let last: u64 = self.config.bucket(euid.timestamp());
+        let (timestamp, regression) = self.config.read_clock(&self.clock, last, self.borrowed);
+        if regression.is_some() {
+            self.last_clock_regression = regression;
+        }
+        let timestamp: u64 = timestamp?;
+        let result: Result<EUID, Error> = if timestamp == last {
+            euid.increment_from(&mut self.random)
+        } else {
+            self.renew(euid, timestamp)
+        };
+        match result {
+            Err(Error::CounterOverflow) => match policy {
+                CounterOverflow::Error => Err(Error::CounterOverflow),
+                CounterOverflow::Borrow => {
+                    let next: u64 = timestamp + self.config.granularity.millis();
+                    let euid: EUID = self.renew(euid, next)?;
+                    self.borrowed = next;
+                    Ok(euid)
+                }
+                CounterOverflow::Spin | CounterOverflow::Sleep => {
+                    let now: u64 = self.config.wait_for_tick(&self.clock, timestamp, policy)?;
+                    self.renew(euid, now)
+                }
+            },
+            result => result,
+        }
+    }
+
+    /// Create random EUID in `bucket`, with the extension of `euid`.
+    fn renew(&mut self, euid: &EUID, bucket: u64) -> Result<EUID, Error> {
+        let timestamp: u64 = self.config.spread(bucket, &mut self.random)?;
+        euid.renew_from(timestamp, &mut self.random)
+    }
+}
+
+impl<C: Clock, R: RandomSource> IntoIterator for Generator<C, R> {
+    type Item = EUID;
+    type IntoIter = EuidIter<C, R>;
+
+    /// Turn this generator into an infinite iterator of strictly ascending EUIDs.
+    fn into_iter(self) -> EuidIter<C, R> {
+        EuidIter {
+            generator: self,
+            extension: None,
+            last: None,
+        }
+    }
+}
+
+/// Infinite iterator of strictly ascending EUIDs.
+///
+/// Each EUID is derived from the previous one like [`Generator::create_batch`] does:
+/// the randomness is incremented within the same millisecond and the next millisecond
+/// is borrowed when it overflows. The iterator yields None only if an EUID can not be
+/// created at all, for example when the clock is after the last representable timestamp,
+/// the extension is more than 15 bits or the random source failed.
+///
+/// Example:
+/// ```rust
+/// use euid::{Generator, ManualClock, EUID};
+///
+/// let euids: Vec<EUID> = Generator::seeded(ManualClock::new(1700000000000), 42)
+///     .into_iter_with_extension(7)
+///     .take(3)
+///     .collect();
+/// assert!(euids[0] < euids[1] && euids[1] < euids[2]);
+/// assert!(euids.iter().all(|euid| euid.extension() == Some(7)));
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct EuidIter<C = SystemClock, R = PooledRandom> {
+    generator: Generator<C, R>,
+    extension: Option<u16>,
+    last: Option<EUID>,
+}
+
+/// Infinite iterator of strictly ascending EUIDs.
+#[cfg(not(feature = "std"))]
+#[derive(Clone, Debug)]
+pub struct EuidIter<C, R> {
+    generator: Generator<C, R>,
+    extension: Option<u16>,
+    last: Option<EUID>,
+}
+
+impl<C: Clock, R: RandomSource> EuidIter<C, R> {
+    /// Returns the generator this iterator creates EUIDs from.
+    pub fn generator(&self) -> &Generator<C, R> {
+        &self.generator
+    }
+}
+
+impl<C: Clock, R: RandomSource> Iterator for EuidIter<C, R> {
+    type Item = EUID;
+
+    fn next(&mut self) -> Option<EUID> {
+        let euid: EUID = self
+            .generator
+            .create_after(self.last.as_ref(), self.extension)
+            .ok()?;
+        self.last = Some(euid);
+        Some(euid)
+    }
+}
+
+/// Thread-safe monotonic EUID generator.
+///
+/// Every EUID created by an `EuidGenerator` is strictly greater than all EUIDs
+/// previously created by the same generator, regardless of the calling thread.
+/// The last created EUID is kept in a 128-bit atomic and advanced with
+/// compare-and-swap, so no lock is taken. If the clock goes backwards, the
+/// [`ClockRegression`] policy decides what happens (default: keep the last
+/// timestamp and increment the randomness).
+///
+/// Randomness is always taken from [`PooledRandom`].
+///
+/// Example:
+/// ```rust
+/// use std::sync::Arc;
+/// use euid::{EuidGenerator, EUID};
+///
+/// let generator: Arc<EuidGenerator> = Arc::new(EuidGenerator::default());
+/// let handle = {
+///     let generator = Arc::clone(&generator);
+///     std::thread::spawn(move || generator.create().unwrap_or_default())
+/// };
+/// let a: EUID = generator.create().unwrap_or_default();
+/// let b: EUID = handle.join().unwrap();
+/// assert_ne!(a, b);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct EuidGenerator<C = SystemClock> {
+    clock: C,
+    extension: Option<u16>,
+    config: Config,
+    last_clock_regression: AtomicU64,
+    borrowed: AtomicU64,
+    borrowing: AtomicU64,
+    last: AtomicU128,
+}
+
+#[cfg(feature = "std")]
+impl Default for EuidGenerator {
+    fn default() -> Self {
+        EuidGenerator::new(SystemClock)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<C: Clock> EuidGenerator<C> {
+    /// Create generator from the given clock.
+    pub fn new(clock: C) -> Self {
+        EuidGenerator {
+            clock,
+            extension: None,
+            config: Config::default(),
+            last_clock_regression: AtomicU64::new(0),
+            borrowed: AtomicU64::new(0),
+            borrowing: AtomicU64::new(0),
+            last: AtomicU128::new(0),
+        }
+    }
+
+    /// Attach `extension` to every EUID created by this generator.
+    /// `create` returns None if the extension is more than 15 bits.
+    pub fn with_extension(mut self, extension: u16) -> Self {
+        self.extension = Some(extension);
+        self
+    }
+
+    /// Count timestamps from `epoch` instead of the Unix epoch.
+    ///
+    /// The epoch is not stored in the EUID: EUIDs created with different epochs do not
+    /// sort or compare meaningfully, and their timestamps must be read back with
+    /// [`EUID::unix_timestamp`] using the same epoch.
+    pub fn with_epoch(mut self, epoch: Epoch) -> Self {
+        self.config.epoch = epoch;
+        self
+    }
+
+    /// Set what `create` does when the clock is behind the last created EUID.
+    pub fn with_clock_regression(mut self, policy: ClockRegression) -> Self {
+        self.config.clock_regression = policy;
+        self
+    }
+
+    /// Set what `create` does when the randomness of the last created EUID can not be incremented.
+    pub fn with_counter_overflow(mut self, policy: CounterOverflow) -> Self {
+        self.config.counter_overflow = policy;
+        self
+    }
+
+    /// Store the creation time with `granularity` instead of the exact millisecond,
+    /// see [`Generator::with_granularity`].
+    pub fn with_granularity(mut self, granularity: Granularity) -> Self {
+        self.config.granularity = granularity;
+        self
+    }
+
+    /// Returns the clock used by this generator.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Returns the extension attached to every EUID created by this generator.
+    pub fn extension(&self) -> Option<u16> {
+        self.extension
+    }
+
+    /// Returns the clock regression policy of this generator.
+    pub fn clock_regression(&self) -> ClockRegression {
+        self.config.clock_regression
+    }
+
+    /// Returns the counter overflow policy of this generator.
+    pub fn counter_overflow(&self) -> CounterOverflow {
+        self.config.counter_overflow
+    }
+
+    /// Returns the epoch the timestamps of this generator count from.
+    pub fn epoch(&self) -> Epoch {
+        self.config.epoch
+    }
+
+    /// Returns the granularity of the creation time of this generator.
+    pub fn granularity(&self) -> Granularity {
+        self.config.granularity
+    }
+
+    /// Returns the size in milliseconds of the most recent clock regression
+    /// observed by this generator, or None if the clock never moved backwards.
+    pub fn last_clock_regression(&self) -> Option<u64> {
+        match self.last_clock_regression.load(Ordering::Relaxed) {
+            0 => None,
+            regression => Some(regression),
+        }
+    }
+
+    /// Create EUID greater than any EUID previously created by this generator.
+    /// None will returns if the clock is after Friday, December 12, 3084 12:41:28.831 PM (UTC),
+    /// the extension is more than 15 bits, overflow happens when incrementing randomness and
+    /// the counter overflow policy is [`CounterOverflow::Error`], or the clock regression policy
+    /// is [`ClockRegression::Error`] and the clock moved backwards.
+    pub fn create(&self) -> Option<EUID> {
+        self.try_create().ok()
+    }
+
+    /// Create EUID greater than any EUID previously created by this generator,
+    /// returning the reason of the failure.
+    pub fn try_create(&self) -> Result<EUID, Error> {
+        self.create_with(self.config.counter_overflow)
+    }
+
+    /// Create EUID greater than any EUID previously created by this generator
+    /// without blocking the thread.
+    ///
+    /// With [`CounterOverflow::Spin`] or [`CounterOverflow::Sleep`], an overflow awaits
+    /// `sleep(duration)` until the clock ticks, see [`Generator::next_async`].
+    pub async fn create_async<F: core::future::Future<Output = ()>>(
+        &self,
+        sleep: impl FnMut(core::time::Duration) -> F,
+    ) -> Option<EUID> {
+        self.try_create_async(sleep).await.ok()
+    }
+
+    /// Create EUID greater than any EUID previously created by this generator
+    /// without blocking the thread, returning the reason of the failure.
+    pub async fn try_create_async<F: core::future::Future<Output = ()>>(
+        &self,
+        mut sleep: impl FnMut(core::time::Duration) -> F,
+    ) -> Result<EUID, Error> {
+        let policy: CounterOverflow = match self.config.counter_overflow {
+            CounterOverflow::Spin | CounterOverflow::Sleep => CounterOverflow::Error,
+            policy => policy,
+        };
+        loop {
+            match self.create_with(policy) {
+                Err(Error::CounterOverflow) if policy != self.config.counter_overflow => {
+                    sleep(self.config.until_next_bucket(&self.clock)).await
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn create_with(&self, policy: CounterOverflow) -> Result<EUID, Error> {
+        let mut last: u128 = self.last.load(Ordering::Acquire);
+        loop {
+            let prev: EUID = EUID::from(last);
+            let bucket: u64 = self.config.bucket(prev.timestamp());
+            let borrowed: u64 = self.borrowed.load(Ordering::Acquire);
+            let (timestamp, regression) = self.config.read_clock(&self.clock, bucket, borrowed);
+            if let Some(regression) = regression {
+                // A borrower raises `borrowed` right after publishing its EUID, wait for
+                // borrowers in flight and re-read.
+                let current: u128 = self.last.load(Ordering::Acquire);
+                if current != last
+                    || self.borrowing.load(Ordering::Acquire) != 0
+                    || self.borrowed.load(Ordering::Acquire) != borrowed
+                {
+                    core::hint::spin_loop();
+                    last = current;
+                    continue;
+                }
+                self.last_clock_regression
+                    .store(regression, Ordering::Relaxed);
+            }
+            let timestamp: u64 = timestamp?;
+            let euid: EUID = if last != 0 && timestamp == bucket {
+                match prev.increment_from(&mut PooledRandom) {
+                    Ok(euid) => euid,
+                    Err(Error::CounterOverflow) if policy == CounterOverflow::Borrow => {
+                        self.create_at(timestamp + self.config.granularity.millis())?
+                    }
+                    Err(Error::CounterOverflow) if policy == CounterOverflow::Error => {
+                        return Err(Error::CounterOverflow)
+                    }
+                    Err(Error::CounterOverflow) => {
+                        self.config.wait_for_tick(&self.clock, timestamp, policy)?;
+                        last = self.last.load(Ordering::Acquire);
+                        continue;
+                    }
+                    Err(e) => return Err(e),
+                }
+            } else {
+                self.create_at(timestamp)?
+            };
+            // Only a published EUID excuses the clock from being behind it.
+            let bucket: u64 = self.config.bucket(euid.timestamp());
+            let borrowing: bool = bucket > timestamp;
+            if borrowing {
+                self.borrowing.fetch_add(1, Ordering::AcqRel);
+            }
+            let result = self.last.compare_exchange_weak(
+                last,
+                u128::from(euid),
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            );
+            if borrowing {
+                if result.is_ok() {
+                    self.borrowed.fetch_max(bucket, Ordering::AcqRel);
+                }
+                self.borrowing.fetch_sub(1, Ordering::AcqRel);
+            }
+            match result {
+                Ok(_) => return Ok(euid),
+                Err(current) => last = current,
+            }
+        }
+    }
+
+    /// Create random EUID in `bucket`.
+    fn create_at(&self, bucket: u64) -> Result<EUID, Error> {
+        let timestamp: u64 = self.config.spread(bucket, &mut PooledRandom)?;
+        match self.extension {
+            Some(ext) => {
+                EUID::create_with_timestamp_and_extension_from(timestamp, ext, &mut PooledRandom)
+            }
+            None => EUID::create_with_timestamp_from(timestamp, &mut PooledRandom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Config;
+    use crate::{
+        Clock, ClockRegression, CounterOverflow, Epoch, Error, Generator, Granularity,
+        RandomSource, EUID,
+    };
+    #[cfg(feature = "std")]
+    use crate::{EuidGenerator, ManualClock};
+
+    struct FixedClock(u64);
+
+    impl Clock for FixedClock {
+        fn current_timestamp(&self) -> u64 {
+            self.0
+        }
+    }
+
+    struct SequenceClock(std::cell::Cell<usize>, Vec<u64>);
+
+    impl SequenceClock {
+        fn new(timestamps: &[u64]) -> Self {
+            SequenceClock(std::cell::Cell::new(0), timestamps.to_vec())
+        }
+
+        fn reads(&self) -> usize {
+            self.0.get()
+        }
+    }
+
+    impl Clock for SequenceClock {
+        fn current_timestamp(&self) -> u64 {
+            let i: usize = self.0.get();
+            self.0.set(i + 1);
+            self.1[i.min(self.1.len() - 1)]
+        }
+    }
+
+    struct CountingRandom(u64);
+
+    impl RandomSource for CountingRandom {
+        fn random_u32(&mut self) -> u32 {
+            self.0 += 1;
+            self.0 as u32
+        }
+
+        fn random_u128(&mut self) -> (u64, u64) {
+            self.0 += 1;
+            (self.0, self.0)
+        }
+    }
+
+    #[test]
+    fn create_test() {
+        let mut generator = Generator::new(FixedClock(1700000000000), CountingRandom(0));
+        let euid: EUID = generator.create().unwrap();
+        assert_eq!(1700000000000, euid.timestamp());
+        assert_eq!(None, euid.extension());
+        assert_eq!(1, u128::from(euid) as u64);
+        assert_eq!(1, generator.random_source().0);
+
+        let euid: EUID = generator.create_with_extension(7).unwrap();
+        assert_eq!(1700000000000, euid.timestamp());
+        assert_eq!(Some(7), euid.extension());
+        assert_eq!(2, u128::from(euid) as u64);
+
+        let mut generator =
+            Generator::new(FixedClock(EUID::TIMESTAMP_BITMASK + 1), CountingRandom(0));
+        assert_eq!(None, generator.create());
+        assert_eq!(None, generator.create_with_extension(1));
+    }
+
+    #[test]
+    fn next_test() {
+        let mut generator = Generator::new(FixedClock(1700000000000), CountingRandom(0));
+        let first: EUID = generator.create_with_extension(3).unwrap();
+        let second: EUID = generator.next(&first).unwrap();
+        assert!(first < second);
+        assert_eq!(first.timestamp(), second.timestamp());
+        assert_eq!(Some(3), second.extension());
+        assert_eq!((1 << 32) | 2, u128::from(second) as u64);
+
+        let mut generator = Generator::new(FixedClock(1700000000001), CountingRandom(0));
+        let third: EUID = generator.next(&second).unwrap();
+        assert!(second < third);
+        assert_eq!(1700000000001, third.timestamp());
+        assert_eq!(Some(3), third.extension());
+
+        let last: EUID = EUID(third.0, u64::MAX);
+        assert_eq!(None, generator.next(&last));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn default_test() {
+        let now: u64 = crate::time::current_timestamp();
+        let mut generator: Generator = Generator::default();
+        let euid: EUID = generator.create().unwrap();
+        assert!(euid.timestamp() >= now);
+        assert!(generator.clock().current_timestamp() >= euid.timestamp());
+        assert!(euid < generator.next(&euid).unwrap());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn euid_generator_test() {
+        let generator = EuidGenerator::new(FixedClock(1700000000000)).with_extension(5);
+        assert_eq!(Some(5), generator.extension());
+        let first: EUID = generator.create().unwrap();
+        assert_eq!(1700000000000, first.timestamp());
+        assert_eq!(Some(5), first.extension());
+        let second: EUID = generator.create().unwrap();
+        assert!(first < second);
+        assert_eq!(first.0, second.0);
+
+        let generator = EuidGenerator::new(FixedClock(1700000000000)).with_extension(0x8000);
+        assert_eq!(None, generator.create());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn euid_generator_clock_regression_test() {
+        let clock = ManualClock::new(1700000000000);
+        let generator = EuidGenerator::new(&clock);
+        assert_eq!(None, generator.last_clock_regression());
+        let first: EUID = generator.create().unwrap();
+        clock.set(1699999999000);
+        let second: EUID = generator.create().unwrap();
+        assert!(first < second);
+        assert_eq!(1700000000000, second.timestamp());
+        assert_eq!(Some(1000), generator.last_clock_regression());
+        clock.set(1700000000001);
+        let third: EUID = generator.create().unwrap();
+        assert!(second < third);
+        assert_eq!(1700000000001, third.timestamp());
+    }
+
+    /// Clock running `hook` once on its next read, after taking the time.
+    #[cfg(feature = "std")]
+    struct HookClock {
+        now: std::sync::atomic::AtomicU64,
+        hook: std::sync::Mutex<Option<Box<dyn FnOnce() + Send>>>,
+    }
+
+    #[cfg(feature = "std")]
+    impl Clock for HookClock {
+        fn current_timestamp(&self) -> u64 {
+            let now: u64 = self.now.load(std::sync::atomic::Ordering::SeqCst);
+            let hook = self.hook.lock().unwrap().take();
+            if let Some(hook) = hook {
+                hook();
+            }
+            now
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn euid_generator_lost_borrow_test() {
+        let clock: &'static HookClock = Box::leak(Box::new(HookClock {
+            now: std::sync::atomic::AtomicU64::new(TS),
+            hook: std::sync::Mutex::new(None),
+        }));
+        let generator: &'static EuidGenerator<&HookClock> = Box::leak(Box::new(
+            EuidGenerator::new(clock)
+                .with_counter_overflow(CounterOverflow::Borrow)
+                .with_clock_regression(ClockRegression::Error),
+        ));
+        let first: EUID = generator.create().unwrap();
+        generator.last.store(
+            u128::from(EUID(first.0, u64::MAX)),
+            portable_atomic::Ordering::SeqCst,
+        );
+        // Right after this create read the clock, the clock ticks and another create
+        // publishes first, so the borrow of TS + 1 loses the compare-and-swap.
+        *clock.hook.lock().unwrap() = Some(Box::new(move || {
+            clock.now.store(TS + 1, std::sync::atomic::Ordering::SeqCst);
+            assert_eq!(TS + 1, generator.create().unwrap().timestamp());
+        }));
+        assert_eq!(TS + 1, generator.create().unwrap().timestamp());
+        assert_eq!(
+            0,
+            generator.borrowed.load(portable_atomic::Ordering::SeqCst)
+        );
+        // Nothing was borrowed, so going back is a regression.
+        clock.now.store(TS, std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(Err(Error::ClockRegression(1)), generator.try_create());
+        assert_eq!(Some(1), generator.last_clock_regression());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn euid_generator_borrow_contention_test() {
+        // Every round starts from an overflowed EUID, so all threads borrow at once.
+        let clock = ManualClock::new(TS);
+        let generator = EuidGenerator::new(&clock)
+            .with_counter_overflow(CounterOverflow::Borrow)
+            .with_clock_regression(ClockRegression::Error);
+        for _ in 0..100 {
+            let prev: EUID = generator.create().unwrap();
+            generator.last.store(
+                u128::from(EUID(prev.0, u64::MAX)),
+                portable_atomic::Ordering::SeqCst,
+            );
+            let euids: Vec<EUID> = std::thread::scope(|scope| {
+                let handles: Vec<_> = (0..4)
+                    .map(|_| scope.spawn(|| generator.create().unwrap()))
+                    .collect();
+                handles.into_iter().map(|h| h.join().unwrap()).collect()
+            });
+            let borrowed: u64 = generator.borrowed.load(portable_atomic::Ordering::SeqCst);
+            assert!(euids.iter().any(|euid| euid.timestamp() == borrowed));
+            assert!(euids.iter().all(|euid| prev < *euid));
+            clock.advance(2);
+        }
+        // The clock passed every borrow, going back is a regression again.
+        let last: EUID = generator.create().unwrap();
+        clock.set(last.timestamp() - 1);
+        assert_eq!(Err(Error::ClockRegression(1)), generator.try_create());
+    }
+
+    #[cfg(feature = "std")]
+    fn assert_concurrent_monotonic<C: Clock + Sync + Send + 'static>(
+        generator: EuidGenerator<C>,
+        threads: usize,
+        count: usize,
+    ) {
+        let generator = std::sync::Arc::new(generator);
+        let handles: Vec<std::thread::JoinHandle<Vec<EUID>>> = (0..threads)
+            .map(|_| {
+                let generator = std::sync::Arc::clone(&generator);
+                std::thread::spawn(move || {
+                    (0..count)
+                        .map(|_| generator.create().unwrap())
+                        .collect::<Vec<EUID>>()
+                })
+            })
+            .collect();
+        let mut all: Vec<EUID> = Vec::with_capacity(threads * count);
+        for handle in handles {
+            let euids: Vec<EUID> = handle.join().unwrap();
+            for i in 1..euids.len() {
+                assert!(euids[i - 1] < euids[i]);
+            }
+            all.extend(euids);
+        }
+        let last: EUID = generator.create().unwrap();
+        assert!(all.iter().all(|euid| *euid < last));
+        all.sort();
+        all.dedup();
+        assert_eq!(threads * count, all.len());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn euid_generator_concurrent_test() {
+        assert_concurrent_monotonic(EuidGenerator::default(), 8, 20000);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn euid_generator_concurrent_frozen_clock_test() {
+        assert_concurrent_monotonic(EuidGenerator::new(FixedClock(1700000000000)), 8, 20000);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn euid_generator_send_sync_test() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<EuidGenerator>();
+    }
+
+    #[test]
+    fn clock_regression_hold_test() {
+        let clock = SequenceClock::new(&[1700000000000, 1699999999990, 1700000000001]);
+        let mut generator = Generator::new(&clock, CountingRandom(0));
+        assert_eq!(ClockRegression::Hold, generator.clock_regression());
+        let first: EUID = generator.create().unwrap();
+        let second: EUID = generator.try_next(&first).unwrap();
+        assert!(first < second);
+        assert_eq!(first.timestamp(), second.timestamp());
+        assert_eq!(Some(10), generator.last_clock_regression());
+        let third: EUID = generator.try_next(&second).unwrap();
+        assert!(second < third);
+        assert_eq!(1700000000001, third.timestamp());
+        assert_eq!(Some(10), generator.last_clock_regression());
+    }
+
+    #[test]
+    fn clock_regression_wait_test() {
+        let clock =
+            SequenceClock::new(&[1700000000000, 1699999999998, 1699999999999, 1700000000000]);
+        let mut generator =
+            Generator::new(&clock, CountingRandom(0)).with_clock_regression(ClockRegression::Wait);
+        let first: EUID = generator.create().unwrap();
+        let second: EUID = generator.try_next(&first).unwrap();
+        assert_eq!(4, clock.reads());
+        assert!(first < second);
+        assert_eq!(first.timestamp(), second.timestamp());
+        assert_eq!(Some(2), generator.last_clock_regression());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn clock_regression_error_test() {
+        let clock = SequenceClock::new(&[1700000000000, 1699999999000, 1700000000000]);
+        let mut generator =
+            Generator::new(&clock, CountingRandom(0)).with_clock_regression(ClockRegression::Error);
+        let first: EUID = generator.create().unwrap();
+        assert_eq!(
+            Err(Error::ClockRegression(1000)),
+            generator.try_next(&first)
+        );
+        assert_eq!(Some(1000), generator.last_clock_regression());
+        assert!(first < generator.try_next(&first).unwrap());
+
+        let clock = ManualClock::new(1700000000000);
+        let generator = EuidGenerator::new(&clock).with_clock_regression(ClockRegression::Error);
+        let first: EUID = generator.create().unwrap();
+        clock.set(1699999999995);
+        assert_eq!(Err(Error::ClockRegression(5)), generator.try_create());
+        assert_eq!(Some(5), generator.last_clock_regression());
+        clock.set(1700000000000);
+        assert!(first < generator.try_create().unwrap());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn next_clock_regression_test() {
+        let future: EUID =
+            EUID::create_with_timestamp(crate::time::current_timestamp() + 60000).unwrap();
+        let next: EUID = future.next().unwrap();
+        assert!(future < next);
+        assert_eq!(future.timestamp(), next.timestamp());
+    }
+
+    #[cfg(feature = "std")]
+    struct CountingWaker(std::sync::atomic::AtomicUsize);
+
+    #[cfg(feature = "std")]
+    impl std::task::Wake for CountingWaker {
+        fn wake(self: std::sync::Arc<Self>) {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    /// Poll `future` to completion, returning its output and the number of wake-ups.
+    #[cfg(feature = "std")]
+    fn block_on<F: std::future::Future>(future: F) -> (F::Output, usize) {
+        let counter = std::sync::Arc::new(CountingWaker(std::sync::atomic::AtomicUsize::new(0)));
+        let waker: std::task::Waker = std::task::Waker::from(std::sync::Arc::clone(&counter));
+        let mut cx = std::task::Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return (output, counter.0.load(std::sync::atomic::Ordering::SeqCst));
+            }
+        }
+    }
+
+    const TS: u64 = 1700000000000;
+
+    #[cfg(feature = "std")]
+    fn overflowed(extension: u16) -> EUID {
+        let euid: EUID = EUID::create_with_timestamp_and_extension(TS, extension).unwrap();
+        EUID(euid.0, u64::MAX)
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn counter_overflow_error_test() {
+        let mut generator = Generator::new(FixedClock(TS), CountingRandom(0));
+        assert_eq!(CounterOverflow::Error, generator.counter_overflow());
+        assert_eq!(
+            Err(Error::CounterOverflow),
+            generator.try_next(&overflowed(1))
+        );
+        assert_eq!(None, generator.next(&overflowed(1)));
+        let (result, wakes) =
+            block_on(generator.try_next_async(&overflowed(1), |_| core::future::ready(())));
+        assert_eq!(Err(Error::CounterOverflow), result);
+        assert_eq!(0, wakes);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn counter_overflow_borrow_test() {
+        let mut generator = Generator::new(FixedClock(TS), CountingRandom(0))
+            .with_counter_overflow(CounterOverflow::Borrow)
+            .with_clock_regression(ClockRegression::Error);
+        let prev: EUID = overflowed(9);
+        let borrowed: EUID = generator.try_next(&prev).unwrap();
+        assert!(prev < borrowed);
+        assert_eq!(TS + 1, borrowed.timestamp());
+        assert_eq!(Some(9), borrowed.extension());
+        let next: EUID = generator.try_next(&borrowed).unwrap();
+        assert!(borrowed < next);
+        assert_eq!(TS + 1, next.timestamp());
+        assert_eq!(borrowed.0, next.0);
+        assert_eq!(None, generator.last_clock_regression());
+
+        let generator =
+            EuidGenerator::new(FixedClock(TS)).with_counter_overflow(CounterOverflow::Borrow);
+        let first: EUID = generator.create().unwrap();
+        generator.last.store(
+            u128::from(EUID(first.0, u64::MAX)),
+            portable_atomic::Ordering::SeqCst,
+        );
+        let borrowed: EUID = generator.create().unwrap();
+        assert_eq!(TS + 1, borrowed.timestamp());
+        let next: EUID = generator.create().unwrap();
+        assert!(borrowed < next);
+        assert_eq!(TS + 1, next.timestamp());
+        assert_eq!(None, generator.last_clock_regression());
+    }
+
+    struct MaxRandom;
+
+    impl RandomSource for MaxRandom {
+        fn random_u32(&mut self) -> u32 {
+            u32::MAX
+        }
+
+        fn random_u128(&mut self) -> (u64, u64) {
+            (u64::MAX, u64::MAX)
+        }
+    }
+
+    fn assert_ascending(euids: &[EUID]) {
+        for w in euids.windows(2) {
+            assert!(w[0] < w[1], "{} >= {}", w[0], w[1]);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn batch_test() {
+        let mut generator =
+            Generator::new(SequenceClock::new(&[TS, TS, TS + 1]), CountingRandom(0));
+        let euids: Vec<EUID> = generator.create_batch(4).unwrap();
+        assert_ascending(&euids);
+        let timestamps: Vec<u64> = euids.iter().map(EUID::timestamp).collect();
+        assert_eq!(vec![TS, TS, TS + 1, TS + 1], timestamps);
+        assert_eq!(Some(Vec::new()), generator.create_batch(0));
+
+        // Every increment overflows: each EUID borrows the next millisecond,
+        // even though the policy of the generator is CounterOverflow::Error.
+        let mut generator = Generator::new(FixedClock(TS), MaxRandom);
+        let euids: Vec<EUID> = generator.create_batch_with_extension(4, 9).unwrap();
+        assert_ascending(&euids);
+        let timestamps: Vec<u64> = euids.iter().map(EUID::timestamp).collect();
+        assert_eq!(vec![TS, TS + 1, TS + 2, TS + 3], timestamps);
+        assert!(euids.iter().all(|euid| euid.extension() == Some(9)));
+        assert_eq!(None, generator.create_batch_with_extension(4, 0x8000));
+
+        let mut euids: [EUID; 3] = [EUID::default(); 3];
+        let mut generator = Generator::new(FixedClock(TS), CountingRandom(0));
+        assert_eq!(Ok(()), generator.fill_with_extension(&mut euids, 1));
+        assert_ascending(&euids);
+        assert_eq!(
+            Err(Error::ExtensionOverflow(0x8000)),
+            generator.fill_with_extension(&mut euids, 0x8000)
+        );
+        assert_eq!(Ok(()), generator.fill(&mut []));
+
+        let mut generator = Generator::new(SequenceClock::new(&[TS, TS - 1]), CountingRandom(0))
+            .with_clock_regression(ClockRegression::Error);
+        assert_eq!(
+            Err(Error::ClockRegression(1)),
+            generator.fill(&mut [EUID::default(); 2])
+        );
+    }
+
+    #[test]
+    fn iter_test() {
+        let generator = Generator::new(FixedClock(TS), MaxRandom);
+        let euids: Vec<EUID> = generator.into_iter().take(100).collect();
+        assert_ascending(&euids);
+        assert_eq!(TS + 99, euids[99].timestamp());
+
+        let mut iter =
+            Generator::new(FixedClock(TS), CountingRandom(0)).into_iter_with_extension(3);
+        let euids: Vec<EUID> = iter.by_ref().take(100).collect();
+        assert_ascending(&euids);
+        assert!(euids.iter().all(|euid| euid.timestamp() == TS));
+        assert!(euids.iter().all(|euid| euid.extension() == Some(3)));
+        assert!(euids[99] < iter.next().unwrap());
+        assert_eq!(TS, iter.generator().clock().0);
+
+        let mut iter =
+            Generator::new(FixedClock(TS), CountingRandom(0)).into_iter_with_extension(0x8000);
+        assert_eq!(None, iter.next());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn counter_overflow_spin_test() {
+        let clock = SequenceClock::new(&[TS, TS, TS, TS + 1]);
+        let mut generator =
+            Generator::new(&clock, CountingRandom(0)).with_counter_overflow(CounterOverflow::Spin);
+        let prev: EUID = overflowed(0);
+        let next: EUID = generator.try_next(&prev).unwrap();
+        assert_eq!(4, clock.reads());
+        assert!(prev < next);
+        assert_eq!(TS + 1, next.timestamp());
+        assert_eq!(Some(0), next.extension());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn counter_overflow_sleep_test() {
+        let clock = SequenceClock::new(&[TS, TS, TS, TS + 1]);
+        let mut generator =
+            Generator::new(&clock, CountingRandom(0)).with_counter_overflow(CounterOverflow::Sleep);
+        let prev: EUID = overflowed(300);
+        let next: EUID = generator.try_next(&prev).unwrap();
+        assert_eq!(4, clock.reads());
+        assert!(prev < next);
+        assert_eq!(TS + 1, next.timestamp());
+        assert_eq!(Some(300), next.extension());
+
+        let clock = SequenceClock::new(&[0, TS, TS, TS + 1]);
+        let generator = EuidGenerator::new(&clock).with_counter_overflow(CounterOverflow::Sleep);
+        let first: EUID = generator.create().unwrap();
+        assert_eq!(0, first.timestamp());
+        generator
+            .last
+            .store(u128::from(overflowed(3)), portable_atomic::Ordering::SeqCst);
+        let next: EUID = generator.create().unwrap();
+        assert_eq!(TS + 1, next.timestamp());
+    }
+
+    /// Timer of a test executor: ready once `clock` reaches `until`, never wakes the task.
+    #[cfg(feature = "std")]
+    struct ClockSleep<'a> {
+        clock: &'a ManualClock,
+        until: u64,
+    }
+
+    #[cfg(feature = "std")]
+    impl std::future::Future for ClockSleep<'_> {
+        type Output = ();
+
+        fn poll(
+            self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<()> {
+            if self.clock.current_timestamp() >= self.until {
+                std::task::Poll::Ready(())
+            } else {
+                std::task::Poll::Pending
+            }
+        }
+    }
+
+    /// Poll `future` once, returning its output if ready and the number of wake-ups.
+    #[cfg(feature = "std")]
+    fn poll_once<F: std::future::Future>(
+        future: std::pin::Pin<&mut F>,
+    ) -> (std::task::Poll<F::Output>, usize) {
+        let counter = std::sync::Arc::new(CountingWaker(std::sync::atomic::AtomicUsize::new(0)));
+        let waker: std::task::Waker = std::task::Waker::from(std::sync::Arc::clone(&counter));
+        let poll = future.poll(&mut std::task::Context::from_waker(&waker));
+        (poll, counter.0.load(std::sync::atomic::Ordering::SeqCst))
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn counter_overflow_async_test() {
+        let clock = ManualClock::new(TS);
+        let mut generator =
+            Generator::new(&clock, CountingRandom(0)).with_counter_overflow(CounterOverflow::Spin);
+        let prev: EUID = overflowed(2);
+        let requested = std::cell::RefCell::new(Vec::new());
+        let sleep = |duration: std::time::Duration| {
+            requested.borrow_mut().push(duration);
+            ClockSleep {
+                clock: &clock,
+                until: clock.current_timestamp() + duration.as_millis() as u64,
+            }
+        };
+        let mut future = Box::pin(generator.next_async(&prev, sleep));
+        // Parked on the timer: pending, not woken, and not re-polled into a new sleep.
+        for _ in 0..3 {
+            assert_eq!((std::task::Poll::Pending, 0), poll_once(future.as_mut()));
+        }
+        assert_eq!(
+            vec![std::time::Duration::from_millis(1)],
+            *requested.borrow()
+        );
+        clock.advance(1);
+        let (next, wakes) = poll_once(future.as_mut());
+        let next: EUID = match next {
+            std::task::Poll::Ready(next) => next.unwrap(),
+            std::task::Poll::Pending => panic!("pending after the clock ticked"),
+        };
+        assert_eq!(0, wakes);
+        assert!(prev < next);
+        assert_eq!(TS + 1, next.timestamp());
+
+        // Sleeps until the next bucket.
+        let clock = ManualClock::new(TS + 1500);
+        let generator = EuidGenerator::new(&clock)
+            .with_granularity(Granularity::Second)
+            .with_counter_overflow(CounterOverflow::Sleep);
+        let first: EUID = generator.create().unwrap();
+        generator.last.store(
+            u128::from(EUID(first.0, u64::MAX)),
+            portable_atomic::Ordering::SeqCst,
+        );
+        let requested = std::cell::RefCell::new(Vec::new());
+        let sleep = |duration: std::time::Duration| {
+            requested.borrow_mut().push(duration);
+            clock.advance(duration.as_millis() as u64);
+            core::future::ready(())
+        };
+        let (next, wakes) = block_on(generator.create_async(sleep));
+        assert_eq!(0, wakes);
+        assert_eq!(
+            vec![std::time::Duration::from_millis(500)],
+            *requested.borrow()
+        );
+        assert_eq!(
+            Granularity::Second.truncate(TS) + 2000,
+            Granularity::Second.truncate(next.unwrap().timestamp())
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn epoch_test() {
+        let epoch: Epoch = Epoch::from_unix_millis(1577836800000);
+        let mut generator = Generator::new(FixedClock(TS), CountingRandom(0)).with_epoch(epoch);
+        assert_eq!(epoch, generator.epoch());
+        let euid: EUID = generator.create_with_extension(4).unwrap();
+        assert_eq!(TS - 1577836800000, euid.timestamp());
+        assert_eq!(TS, euid.unix_timestamp(epoch));
+        assert_eq!(
+            std::time::UNIX_EPOCH + std::time::Duration::from_millis(TS),
+            euid.datetime_with_epoch(epoch)
+        );
+        let next: EUID = generator.next(&euid).unwrap();
+        assert!(euid < next);
+        assert_eq!(TS, next.unix_timestamp(epoch));
+
+        let generator = EuidGenerator::new(FixedClock(TS)).with_epoch(epoch);
+        assert_eq!(epoch, generator.epoch());
+        assert_eq!(TS, generator.create().unwrap().unix_timestamp(epoch));
+
+        let mut generator = Generator::new(FixedClock(TS), CountingRandom(0))
+            .with_epoch(Epoch::from_unix_millis(TS + 1));
+        assert_eq!(None, generator.create());
+        assert_eq!(Err(Error::BeforeEpoch(TS)), generator.try_next(&euid));
+        let generator =
+            EuidGenerator::new(FixedClock(TS)).with_epoch(Epoch::from_unix_millis(TS + 1));
+        assert_eq!(Err(Error::BeforeEpoch(TS)), generator.try_create());
+    }
+
+    #[test]
+    fn epoch_limit_test() {
+        let epoch: Epoch = Epoch::from_unix_millis(1577836800000);
+        let limit: u64 = Epoch::UNIX.max_unix_millis();
+        let mut generator = Generator::new(FixedClock(limit + 1), CountingRandom(0));
+        assert_eq!(None, generator.create());
+        let mut generator =
+            Generator::new(FixedClock(limit + 1), CountingRandom(0)).with_epoch(epoch);
+        let euid: EUID = generator.create().unwrap();
+        assert_eq!(limit + 1, euid.unix_timestamp(epoch));
+        let mut generator =
+            Generator::new(FixedClock(epoch.max_unix_millis() + 1), CountingRandom(0))
+                .with_epoch(epoch);
+        assert_eq!(None, generator.create());
+    }
+
+    #[cfg(feature = "std")]
+    fn seeded_sequence(seed: u64) -> Vec<String> {
+        let clock = ManualClock::new(TS);
+        let mut generator = Generator::seeded(&clock, seed);
+        let mut euids: Vec<EUID> = vec![generator.create().unwrap()];
+        euids.push(generator.create_with_extension(0x1234).unwrap());
+        euids.push(generator.next(&euids[1]).unwrap());
+        clock.advance(1);
+        euids.push(generator.next(&euids[2]).unwrap());
+        euids.push(generator.next(&euids[3]).unwrap());
+        euids.iter().map(|euid| euid.encode(true)).collect()
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn seeded_test() {
+        assert_eq!(seeded_sequence(42), seeded_sequence(42));
+        assert_ne!(seeded_sequence(42), seeded_sequence(43));
+        assert_eq!(
+            vec![
+                "1HF7YAT00VMN0A7FWCSV4SQH0DP",
+                "1HF7YAT00MHMTP0WW7ZGWJQ3JG4",
+                "1HF7YAT00MHMTP0WW800KF2RBB4",
+                "1HF7YAT01MHMTDZ9CWE4ADVDBQ8",
+                "1HF7YAT01MHMTDZ9CWEWSXHNXTW",
+            ],
+            seeded_sequence(42)
+        );
+    }
+
+    struct FailingRandom;
+
+    impl RandomSource for FailingRandom {
+        fn random_u32(&mut self) -> u32 {
+            0
+        }
+
+        fn random_u128(&mut self) -> (u64, u64) {
+            (0, 0)
+        }
+
+        fn try_random_u32(&mut self) -> Result<u32, Error> {
+            Err(Error::EntropyUnavailable)
+        }
+
+        fn try_random_u128(&mut self) -> Result<(u64, u64), Error> {
+            Err(Error::EntropyUnavailable)
+        }
+    }
+
+    #[test]
+    fn entropy_unavailable_test() {
+        let mut generator = Generator::new(FixedClock(TS), FailingRandom);
+        assert_eq!(None, generator.create());
+        assert_eq!(None, generator.create_with_extension(1));
+        let euid: EUID = EUID::create_with_timestamp_from(TS, &mut CountingRandom(0)).unwrap();
+        assert_eq!(Err(Error::EntropyUnavailable), generator.try_next(&euid));
+        let mut generator = Generator::new(FixedClock(TS + 1), FailingRandom);
+        assert_eq!(Err(Error::EntropyUnavailable), generator.try_next(&euid));
+    }
+
+    #[test]
+    fn spread_test() {
+        let config = Config {
+            granularity: Granularity::Second,
+            ..Config::default()
+        };
+        // `CountingRandom` draws one more than it holds.
+        assert_eq!(TS, config.spread(TS, &mut CountingRandom(0)).unwrap());
+        assert_eq!(
+            TS + 500,
+            config
+                .spread(TS, &mut CountingRandom(u64::MAX / 2))
+                .unwrap()
+        );
+        assert_eq!(
+            TS + 999,
+            config
+                .spread(TS, &mut CountingRandom(u64::MAX - 1))
+                .unwrap()
+        );
+        let config = Config::default();
+        assert_eq!(
+            TS,
+            config
+                .spread(TS, &mut CountingRandom(u64::MAX - 1))
+                .unwrap()
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn granularity_test() {
+        for granularity in [Granularity::Second, Granularity::Minute, Granularity::Hour] {
+            let clock = ManualClock::new(TS);
+            let bucket: u64 = granularity.truncate(TS);
+            let mut generator = Generator::seeded(&clock, 42).with_granularity(granularity);
+            assert_eq!(granularity, generator.granularity());
+            let mut euids: Vec<EUID> = Vec::new();
+            for _ in 0..1000 {
+                let euid: EUID = generator.create().unwrap();
+                assert!((bucket..bucket + granularity.millis()).contains(&euid.timestamp()));
+                euids.push(euid);
+            }
+            assert!(euids
+                .iter()
+                .any(|euid| euid.timestamp() != euids[0].timestamp()));
+
+            let mut prev: EUID = generator.create_with_extension(7).unwrap();
+            for _ in 0..1000 {
+                clock.advance(granularity.millis() / 100);
+                let next: EUID = generator.next(&prev).unwrap();
+                assert!(prev < next);
+                assert_eq!(Some(7), next.extension());
+                if granularity.truncate(clock.current_timestamp()) == bucket {
+                    assert_eq!(prev.timestamp(), next.timestamp());
+                } else {
+                    assert!(next.timestamp() >= granularity.truncate(clock.current_timestamp()));
+                }
+                prev = next;
+            }
+
+            let generator = EuidGenerator::new(&clock).with_granularity(granularity);
+            assert_eq!(granularity, generator.granularity());
+            let bucket: u64 = granularity.truncate(clock.current_timestamp());
+            let mut prev: EUID = generator.create().unwrap();
+            for _ in 0..1000 {
+                let next: EUID = generator.create().unwrap();
+                assert!(prev < next);
+                assert_eq!(prev.timestamp(), next.timestamp());
+                assert!((bucket..bucket + granularity.millis()).contains(&next.timestamp()));
+                prev = next;
+            }
+        }
+        let generator = Generator::new(FixedClock(TS), CountingRandom(0));
+        assert_eq!(Granularity::Millisecond, generator.granularity());
+    }
+
+    #[test]
+    fn granularity_overflow_test() {
+        let mut generator = Generator::new(FixedClock(TS), MaxRandom)
+            .with_granularity(Granularity::Second)
+            .with_counter_overflow(CounterOverflow::Borrow);
+        let max: EUID = generator.create().unwrap();
+        let next: EUID = generator.next(&max).unwrap();
+        assert!(max < next);
+        assert_eq!(
+            Granularity::Second.truncate(TS) + 1000,
+            Granularity::Second.truncate(next.timestamp())
+        );
+    }
+}
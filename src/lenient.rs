@@ -0,0 +1,272 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{base32, Error, EUID};
+
+/// Normalization applied by [`EUID::parse_lenient`] before decoding.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Normalization {
+    /// Leading or trailing whitespace was removed.
+    pub trimmed: bool,
+    /// A `urn:`-style prefix (everything up to the last colon) was removed.
+    pub prefix_stripped: bool,
+    /// Number of separators (whitespace, hyphens, underscores, dots) removed.
+    pub separators_removed: usize,
+    /// Number of full-width or confusable characters folded to ASCII.
+    pub characters_folded: usize,
+}
+
+impl Normalization {
+    /// Returns true if the input was decoded as is.
+    pub fn is_empty(&self) -> bool {
+        *self == Normalization::default()
+    }
+}
+
+impl core::fmt::Display for Normalization {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if self.is_empty() {
+            return f.write_str("no normalization");
+        }
+        let mut sep: &str = "";
+        if self.trimmed {
+            write!(f, "trimmed whitespace")?;
+            sep = ", ";
+        }
+        if self.prefix_stripped {
+            write!(f, "{}stripped prefix", sep)?;
+            sep = ", ";
+        }
+        if self.separators_removed > 0 {
+            write!(f, "{}removed {} separator(s)", sep, self.separators_removed)?;
+            sep = ", ";
+        }
+        if self.characters_folded > 0 {
+            write!(f, "{}folded {} character(s)", sep, self.characters_folded)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error returned by [`EUID::parse_lenient`].
+///
+/// Offsets in [`Error::InvalidCharacter`] are byte offsets in the original input,
+/// [`Error::InvalidLength`] counts the symbols left after normalization.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LenientError {
+    /// Why decoding failed.
+    pub error: Error,
+    /// Normalization applied before the failure.
+    pub normalization: Normalization,
+}
+
+impl core::fmt::Display for LenientError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{} ({})", self.error, self.normalization)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LenientError {}
+
+/// Character with its folding, if it is not a symbol by itself.
+enum Folded {
+    Symbol(char),
+    Separator,
+    Prefix,
+}
+
+/// Fold full-width forms and Cyrillic or Greek look-alikes to ASCII, NFKC style.
+fn fold(c: char) -> char {
+    match c {
+        // Full-width ASCII (NFKC maps U+FF01..U+FF5E to U+0021..U+007E).
+        '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+        // Hyphens, dashes and minus signs.
+        '\u{2010}'..='\u{2015}' | '\u{2212}' | '\u{fe58}' | '\u{fe63}' => '-',
+        'А' | 'Α' => 'A',
+        'В' | 'Β' => 'B',
+        'С' => 'C',
+        'Е' | 'Ε' => 'E',
+        'Н' | 'Η' => 'H',
+        'І' | 'Ι' => 'I',
+        'К' | 'Κ' => 'K',
+        'М' | 'Μ' => 'M',
+        'Ν' => 'N',
+        'О' | 'Ο' => 'O',
+        'Р' | 'Ρ' => 'P',
+        'Т' | 'Τ' => 'T',
+        'Х' | 'Χ' => 'X',
+        'У' | 'Υ' => 'Y',
+        'Ζ' => 'Z',
+        'а' | 'α' => 'a',
+        'с' => 'c',
+        'е' => 'e',
+        'і' | 'ι' => 'i',
+        'к' | 'κ' => 'k',
+        'о' | 'ο' => 'o',
+        'р' | 'ρ' => 'p',
+        'х' | 'χ' => 'x',
+        'у' => 'y',
+        _ => c,
+    }
+}
+
+fn classify(c: char) -> Folded {
+    match c {
+        ':' => Folded::Prefix,
+        '-' | '_' | '.' => Folded::Separator,
+        c if c.is_whitespace() => Folded::Separator,
+        c => Folded::Symbol(c),
+    }
+}
+
+pub fn parse_lenient(input: &str) -> Result<EUID, LenientError> {
+    let mut normalization: Normalization = Normalization::default();
+    let trimmed: &str = input.trim();
+    normalization.trimmed = trimmed.len() != input.len();
+    let start: usize = input.len() - input.trim_start().len();
+    // Everything up to the last (folded) colon is a prefix.
+    let mut body: usize = 0;
+    for (offset, c) in trimmed.char_indices() {
+        if let Folded::Prefix = classify(fold(c)) {
+            body = offset + c.len_utf8();
+            normalization.prefix_stripped = true;
+        }
+    }
+    let mut symbols: [u8; 27] = [0u8; 27];
+    let mut n: usize = 0;
+    for (offset, c) in trimmed[body..].char_indices() {
+        let folded: char = fold(c);
+        let symbol: char = match classify(folded) {
+            Folded::Symbol(symbol) => symbol,
+            _ => {
+                normalization.separators_removed += 1;
+                continue;
+            }
+        };
+        if folded != c {
+            normalization.characters_folded += 1;
+        }
+        if !symbol.is_ascii() || !base32::is_symbol(symbol as u8) {
+            return Err(LenientError {
+                error: Error::InvalidCharacter(c, start + body + offset),
+                normalization,
+            });
+        }
+        if n < 27 {
+            symbols[n] = symbol as u8;
+        }
+        n += 1;
+    }
+    if n != 27 {
+        return Err(LenientError {
+            error: Error::InvalidLength(n, 27),
+            normalization,
+        });
+    }
+    base32::decode_bytes(&symbols).map_err(|error| LenientError {
+        error,
+        normalization,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{Error, LenientError, Normalization, EUID};
+
+    const CANONICAL: &str = "C8ZM14GR4JXG0MQXVY18S8TJNA6";
+
+    #[test]
+    fn parse_lenient_test() {
+        let euid: EUID = CANONICAL.parse().unwrap();
+        assert_eq!(Ok(euid), EUID::parse_lenient(CANONICAL));
+        assert_eq!(
+            Ok(euid),
+            EUID::parse_lenient("  C8ZM14GR4JXG0MQXVY18S8TJNA6\n")
+        );
+        assert_eq!(
+            Ok(euid),
+            EUID::parse_lenient("C8ZM1-4GR4J-XG0MQ-XVY18-S8TJN-A6")
+        );
+        assert_eq!(
+            Ok(euid),
+            EUID::parse_lenient("c8zm1 4gr4j xg0mq xvy18 s8tjn a6")
+        );
+        assert_eq!(
+            Ok(euid),
+            EUID::parse_lenient("urn:euid:C8ZM14GR4JXG0MQXVY18S8TJNA6")
+        );
+        // Full-width digits and letters, full-width colon and an en dash.
+        assert_eq!(
+            Ok(euid),
+            EUID::parse_lenient("ｅｕｉｄ：Ｃ８ＺＭ１４ＧＲ４Ｊ–XG0MQXVY18S8TJNA6")
+        );
+        // Cyrillic С and Greek Μ.
+        assert_eq!(Ok(euid), EUID::parse_lenient("С8ZΜ14GR4JXG0MQXVY18S8TJNA6"));
+        // Crockford aliases are handled by the decoder itself.
+        assert_eq!(Ok(euid), EUID::parse_lenient("C8ZM14GR4JXGOMQXVYl8S8TJNA6"));
+    }
+
+    #[test]
+    fn parse_lenient_error_test() {
+        assert_eq!(
+            Err(LenientError {
+                error: Error::InvalidCharacter('U', 17),
+                normalization: Normalization {
+                    trimmed: true,
+                    prefix_stripped: true,
+                    separators_removed: 1,
+                    characters_folded: 0,
+                },
+            }),
+            EUID::parse_lenient(" urn:euid:C8ZM1-4UR4JXG0MQXVY18S8TJNA6")
+        );
+        let err: LenientError =
+            EUID::parse_lenient("Ｃ８ＺＭ１４ＧＲ４ＪＸＧ０ＭＱＸＶＹ１８Ｓ８ＴＪＮ＃６")
+                .unwrap_err();
+        assert_eq!(Error::InvalidCharacter('＃', 75), err.error);
+        assert_eq!(26, err.normalization.characters_folded);
+        assert_eq!(
+            "invalid character '＃' at byte offset 75 (folded 26 character(s))",
+            err.to_string()
+        );
+        assert_eq!(
+            Err(LenientError {
+                error: Error::InvalidLength(28, 27),
+                normalization: Normalization::default(),
+            }),
+            EUID::parse_lenient("C8ZM14GR4JXG0MQXVY18S8TJNA6A")
+        );
+        let err: LenientError =
+            EUID::parse_lenient("C8ZM1-4GR4J-XG0MQ-XVY18-S8TJN-A7").unwrap_err();
+        assert_eq!(Error::InvalidCheckmod(71, 70), err.error);
+        assert_eq!(
+            "invalid check-mod 71, expected 70 (removed 5 separator(s))",
+            err.to_string()
+        );
+        assert_eq!(
+            Error::InvalidLength(0, 27),
+            EUID::parse_lenient(" - ").unwrap_err().error
+        );
+    }
+}
@@ -23,21 +23,194 @@
 //! # EUID
 //! Reference implementation of EUID.
 //!
+//! ## Features
+//! - `std` (default): system clock, thread-local entropy pool and the `EUID::create*`,
+//!   `EUID::next` and [`EuidGenerator`] APIs built on them. Implies `alloc` and `getrandom`.
+//! - `alloc`: `String` and `Vec` returning conveniences such as `EUID::encode`.
+//! - `getrandom`: [`OsRandom`], also available without `std` on targets `getrandom` supports.
+//!
+//! Without default features the crate is `#![no_std]`: the layout, [`EuidBuilder`],
+//! encoding (`Display`), decoding (`FromStr`) and check-mod need no allocator, and EUIDs
+//! are created by a [`Generator`] from a caller supplied [`Clock`] and [`RandomSource`].
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 mod base32;
+mod builder;
+mod canonical;
 mod check;
+mod cipher;
+#[cfg(feature = "alloc")]
+mod correct;
+mod encoded;
+mod format;
+mod generator;
+mod lenient;
+mod policy;
 mod random;
+mod scan;
+mod secret;
+mod signer;
+mod siphash;
+mod sniff;
 mod time;
 
+pub use base32::DecodeReport;
+pub use builder::{EuidBuilder, Parts};
+pub use canonical::{canonicalize, canonicalize_all, Canonicalize};
+pub use cipher::EuidCipher;
+#[cfg(feature = "alloc")]
+pub use correct::{Correction, Edit};
+pub use encoded::EncodedEuid;
+pub use format::{Case, EncodeOptions};
+#[cfg(feature = "std")]
+pub use generator::EuidGenerator;
+pub use generator::{ClockRegression, CounterOverflow, EuidIter, Generator};
+pub use lenient::{LenientError, Normalization};
+pub use policy::{ExtensionRule, ValidationPolicy, Violation, Violations};
+#[cfg(feature = "getrandom")]
+pub use random::OsRandom;
+#[cfg(feature = "std")]
+pub use random::PooledRandom;
+pub use random::{RandomSource, SeededRandom};
+#[cfg(feature = "std")]
+pub use scan::ScanReader;
+pub use scan::{scan, Scan, ScanOptions};
+pub use secret::SecretEuid;
+pub use signer::EuidSigner;
+pub use sniff::{sniff, Confidence, IdFormat, Sniffed};
+#[cfg(feature = "std")]
+pub use time::SystemClock;
+pub use time::{Clock, Epoch, Granularity, ManualClock};
+
 /// Error enum.
 #[derive(Debug, PartialEq, Eq)]
 pub enum Error {
     /// EUID must have 27 character in size.
     InvalidLength(usize, usize),
     /// EUID use a set of 10 digits and 22 letters, excluding 4 of the 26 letters: I L O U.
-    InvalidCharacter(char),
+    /// Carries the invalid character and its byte offset in the input.
+    InvalidCharacter(char, usize),
     /// Invalid entry (typo).
     InvalidCheckmod(usize, usize),
+    /// Lowercase letter or `O`, `I`, `L` alias rejected by strict decoding.
+    /// Carries the character and its byte offset in the input.
+    NonCanonicalCharacter(char, usize),
+    /// EUID without check-mod (check-mod symbol value 127) rejected by strict decoding.
+    MissingCheckmod,
+    /// Timestamp is after Friday, December 12, 3084 12:41:28.831 PM (UTC).
+    TimestampOverflow(u64),
+    /// Extension (user attached data) is more than 15 bits.
+    ExtensionOverflow(u16),
+    /// Extension length (number of extension bits) is more than 15.
+    ExtensionLengthOverflow(u8),
+    /// Filler random bits do not fit next to the extension.
+    FillerOverflow(u16),
+    /// Randomness overflow when deriving monotonic EUID within the same millisecond.
+    CounterOverflow,
+    /// Clock moved backwards by the given number of milliseconds.
+    ClockRegression(u64),
+    /// Clock (milliseconds since the Unix epoch) is before the epoch of the generator.
+    BeforeEpoch(u64),
+    /// Random source failed to provide entropy.
+    EntropyUnavailable,
+    /// Signing key (carries its key id) was removed from the signer.
+    MissingSigningKey(u8),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Error::InvalidLength(len, expected) => {
+                write!(f, "invalid length {}, expected {}", len, expected)
+            }
+            Error::InvalidCharacter(c, offset) => {
+                write!(f, "invalid character {:?} at byte offset {}", c, offset)
+            }
+            Error::InvalidCheckmod(check, expected) => {
+                write!(f, "invalid check-mod {}, expected {}", check, expected)
+            }
+            Error::NonCanonicalCharacter(c, offset) => {
+                write!(
+                    f,
+                    "non-canonical character {:?} at byte offset {}",
+                    c, offset
+                )
+            }
+            Error::MissingCheckmod => write!(f, "missing check-mod"),
+            Error::TimestampOverflow(timestamp) => {
+                write!(f, "timestamp {} is more than 45 bits", timestamp)
+            }
+            Error::ExtensionOverflow(extension) => {
+                write!(f, "extension {} does not fit in its bits", extension)
+            }
+            Error::ExtensionLengthOverflow(ext_len) => {
+                write!(f, "extension length {} is more than 15", ext_len)
+            }
+            Error::FillerOverflow(filler) => {
+                write!(f, "filler {} does not fit next to the extension", filler)
+            }
+            Error::CounterOverflow => write!(f, "randomness overflow within the same millisecond"),
+            Error::ClockRegression(ms) => write!(f, "clock moved backwards by {} ms", ms),
+            Error::BeforeEpoch(now) => write!(f, "clock {} is before the epoch", now),
+            Error::EntropyUnavailable => write!(f, "random source failed to provide entropy"),
+            Error::MissingSigningKey(key_id) => write!(f, "signing key {} was removed", key_id),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Decode EUID literal at compile time.
+///
+/// The literal is decoded in a constant, so a wrong length, an invalid character or a
+/// wrong check-mod fails the build instead of panicking at runtime. Literals without
+/// check-mod (check-mod symbol value 127) are accepted, like `EUID::from_str` does.
+///
+/// Example:
+/// ```rust
+/// use euid::{euid, EUID};
+///
+/// const SYSTEM_TENANT: EUID = euid!("C8ZM14GR4JXG0MQXVY18S8TJNBZ");
+/// assert_eq!("C8ZM14GR4JXG0MQXVY18S8TJNBZ", SYSTEM_TENANT.encode(false));
+/// ```
+///
+/// Wrong length:
+/// ```compile_fail
+/// const BAD: euid::EUID = euid::euid!("C8ZM14GR4JXG0MQXVY18S8TJNB");
+/// ```
+///
+/// Invalid character:
+/// ```compile_fail
+/// const BAD: euid::EUID = euid::euid!("C8ZM14GR4JXG0MQXVY18S8TJNBU");
+/// ```
+///
+/// Wrong check-mod:
+/// ```compile_fail
+/// const BAD: euid::EUID = euid::euid!("C8X2HA87098A0W837DX13FEAWVV");
+/// ```
+#[macro_export]
+macro_rules! euid {
+    ($encoded:literal) => {{
+        const EUID: $crate::EUID = match $crate::EUID::decode_bytes($encoded.as_bytes()) {
+            Ok(euid) => euid,
+            Err($crate::Error::InvalidLength(..)) => {
+                panic!("invalid EUID literal: expected 27 characters")
+            }
+            Err($crate::Error::InvalidCharacter(..)) => {
+                panic!("invalid EUID literal: invalid character")
+            }
+            Err($crate::Error::InvalidCheckmod(..)) => {
+                panic!("invalid EUID literal: wrong check-mod")
+            }
+            Err(_) => panic!("invalid EUID literal"),
+        };
+        EUID
+    }};
 }
 
 /// Extendable Universally Unique Identifier or EUID contains two main components:
@@ -62,9 +235,12 @@ pub struct EUID(u64, u64);
 
 /// A Standard implementation of EUID.
 impl EUID {
-    const TIMESTAMP_BITMASK: u64 = 0x1fffffffffff;
-    const EXT_LEN_BITMASK: u64 = 0xf;
-    const EXT_DATA_BITMASK: u64 = 0x7fff;
+    /// Largest timestamp (45 bits).
+    pub const TIMESTAMP_BITMASK: u64 = 0x1fffffffffff;
+    /// Largest extension length (4 bits).
+    pub const EXT_LEN_BITMASK: u64 = 0xf;
+    /// Largest extension (15 bits).
+    pub const EXT_DATA_BITMASK: u64 = 0x7fff;
 
     /// Create random EUID.
     /// None will returns if the EUID is created after Friday, December 12, 3084 12:41:28.831 PM (UTC).
@@ -78,8 +254,26 @@ impl EUID {
     /// println!("{}", euid.encode(true)); // with check-mod.
     /// println!("{}", euid.encode(false)); // without check-mod.
     /// ```
+    #[cfg(feature = "std")]
     pub fn create() -> Option<EUID> {
-        EUID::create_with_timestamp(time::current_timestamp())
+        EUID::try_create().ok()
+    }
+
+    /// Create random EUID, returning the reason of the failure.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::{Error, EUID};
+    ///
+    /// match EUID::try_create() {
+    ///     Ok(euid) => println!("{}", euid),
+    ///     Err(Error::EntropyUnavailable) => eprintln!("no entropy"),
+    ///     Err(e) => eprintln!("{}", e),
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn try_create() -> Result<EUID, Error> {
+        Generator::new(SystemClock, PooledRandom).try_create()
     }
 
     /// Create random EUID with attachable data (max 15 bit).
@@ -98,12 +292,122 @@ impl EUID {
     /// let overflowed_euid: Option<EUID> = EUID::create_with_extension(32768);
     /// assert_eq!(None, overflowed_euid);
     /// ```
+    #[cfg(feature = "std")]
     pub fn create_with_extension(extension: u16) -> Option<EUID> {
-        if extension > ((EUID::EXT_DATA_BITMASK) as u16) {
-            None
-        } else {
-            EUID::create_with_timestamp_and_extension(time::current_timestamp(), extension)
-        }
+        EUID::try_create_with_extension(extension).ok()
+    }
+
+    /// Create random EUID with attachable data (max 15 bit), returning the reason of the failure.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::{Error, EUID};
+    ///
+    /// assert_eq!(Err(Error::ExtensionOverflow(32768)), EUID::try_create_with_extension(32768));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn try_create_with_extension(extension: u16) -> Result<EUID, Error> {
+        Generator::new(SystemClock, PooledRandom).try_create_with_extension(extension)
+    }
+
+    /// Create `n` EUIDs in strictly ascending order.
+    ///
+    /// The randomness is incremented within the same millisecond and the next millisecond
+    /// is borrowed when it overflows, so the batch does not fail because of its size.
+    /// None will returns if the EUIDs are created after Friday, December 12, 3084 12:41:28.831 PM (UTC).
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::EUID;
+    ///
+    /// let euids: Vec<EUID> = EUID::create_batch(1000).unwrap_or_default();
+    /// assert!(euids.windows(2).all(|w| w[0] < w[1]));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn create_batch(n: usize) -> Option<alloc::vec::Vec<EUID>> {
+        EUID::try_create_batch(n).ok()
+    }
+
+    /// Create `n` EUIDs in strictly ascending order, returning the reason of the failure.
+    #[cfg(feature = "std")]
+    pub fn try_create_batch(n: usize) -> Result<alloc::vec::Vec<EUID>, Error> {
+        Generator::new(SystemClock, PooledRandom).try_create_batch(n)
+    }
+
+    /// Create `n` EUIDs in strictly ascending order with attachable data (max 15 bit).
+    /// None will returns if the EUIDs are created after Friday, December 12, 3084 12:41:28.831 PM (UTC)
+    /// or the extenstion (user attached data) is more then 15 bits.
+    #[cfg(feature = "std")]
+    pub fn create_batch_with_extension(n: usize, extension: u16) -> Option<alloc::vec::Vec<EUID>> {
+        EUID::try_create_batch_with_extension(n, extension).ok()
+    }
+
+    /// Create `n` EUIDs in strictly ascending order with attachable data (max 15 bit),
+    /// returning the reason of the failure.
+    #[cfg(feature = "std")]
+    pub fn try_create_batch_with_extension(
+        n: usize,
+        extension: u16,
+    ) -> Result<alloc::vec::Vec<EUID>, Error> {
+        Generator::new(SystemClock, PooledRandom).try_create_batch_with_extension(n, extension)
+    }
+
+    /// Overwrite `euids` with EUIDs in strictly ascending order, like `create_batch`.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::EUID;
+    ///
+    /// let mut euids: [EUID; 64] = [EUID::default(); 64];
+    /// assert_eq!(Ok(()), EUID::fill(&mut euids));
+    /// assert!(euids.windows(2).all(|w| w[0] < w[1]));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn fill(euids: &mut [EUID]) -> Result<(), Error> {
+        Generator::new(SystemClock, PooledRandom).fill(euids)
+    }
+
+    /// Overwrite `euids` with EUIDs in strictly ascending order with attachable data (max 15 bit),
+    /// like `create_batch_with_extension`.
+    #[cfg(feature = "std")]
+    pub fn fill_with_extension(euids: &mut [EUID], extension: u16) -> Result<(), Error> {
+        Generator::new(SystemClock, PooledRandom).fill_with_extension(euids, extension)
+    }
+
+    /// Returns an infinite iterator of strictly ascending EUIDs.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::EUID;
+    ///
+    /// for euid in EUID::iter().take(10) {
+    ///     println!("{}", euid);
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn iter() -> EuidIter {
+        Generator::new(SystemClock, PooledRandom).into_iter()
+    }
+
+    /// Returns an infinite iterator of strictly ascending EUIDs with attachable data (max 15 bit).
+    #[cfg(feature = "std")]
+    pub fn iter_with_extension(extension: u16) -> EuidIter {
+        Generator::new(SystemClock, PooledRandom).into_iter_with_extension(extension)
+    }
+
+    /// Create EUID from its 128-bit value, same as `EUID::from` but usable in constants.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::EUID;
+    ///
+    /// const ZERO: EUID = EUID::from_u128(0);
+    /// assert_eq!(EUID::default(), ZERO);
+    /// ```
+    pub const fn from_u128(value: u128) -> EUID {
+        let hi: u64 = (value >> 64) as u64;
+        let lo: u64 = (value & 0xffffffffffffffff) as u64;
+        EUID(hi, lo)
     }
 
     /// Returns user attached data (extension), or None if no attached data.
@@ -117,32 +421,116 @@ impl EUID {
         }
     }
 
-    /// Returns timestamp in milliseconds.
+    /// Returns timestamp in milliseconds since the epoch the EUID was created with
+    /// (the Unix epoch unless created by a generator with a custom [`Epoch`]).
+    ///
+    /// EUIDs created with a [`Granularity`] coarser than a millisecond carry a random
+    /// offset in the low bits, only the bucket, e.g. `Granularity::Second.truncate(ts)`,
+    /// is meaningful. The granularity is not recorded in the EUID.
     pub fn timestamp(&self) -> u64 {
         (self.0 >> 19) & EUID::TIMESTAMP_BITMASK
     }
 
+    /// Returns timestamp in milliseconds since the Unix epoch,
+    /// given the epoch the EUID was created with, saturating at `u64::MAX`.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::{Epoch, EUID};
+    ///
+    /// let euid: EUID = EUID::create().unwrap_or_default();
+    /// assert_eq!(euid.timestamp(), euid.unix_timestamp(Epoch::UNIX));
+    /// ```
+    pub fn unix_timestamp(&self, epoch: Epoch) -> u64 {
+        epoch.unix_millis().saturating_add(self.timestamp())
+    }
+
+    /// Returns creation time of an EUID created with the Unix epoch.
+    #[cfg(feature = "std")]
+    pub fn datetime(&self) -> std::time::SystemTime {
+        self.datetime_with_epoch(Epoch::UNIX)
+    }
+
+    /// Returns creation time, given the epoch the EUID was created with.
+    #[cfg(feature = "std")]
+    pub fn datetime_with_epoch(&self, epoch: Epoch) -> std::time::SystemTime {
+        std::time::UNIX_EPOCH + std::time::Duration::from_millis(self.unix_timestamp(epoch))
+    }
+
+    /// Returns the fields of this EUID.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::{Parts, EUID};
+    ///
+    /// let euid: EUID = EUID::create_with_extension(1).unwrap_or_default();
+    /// let parts: Parts = euid.parts();
+    /// assert_eq!(euid.timestamp(), parts.timestamp);
+    /// assert_eq!(Some(parts.extension), euid.extension());
+    /// assert_eq!(Ok(euid), EUID::from_parts(parts));
+    /// ```
+    pub fn parts(&self) -> Parts {
+        let ext_len: u64 = self.0 & EUID::EXT_LEN_BITMASK;
+        Parts {
+            timestamp: self.timestamp(),
+            ext_len: ext_len as u8,
+            extension: self.extension().unwrap_or_default(),
+            filler: ((self.0 >> (4 + ext_len)) & ((1 << (15 - ext_len)) - 1)) as u16,
+            r_hi: (self.1 >> 32) as u32,
+            r_lo: self.1 as u32,
+        }
+    }
+
+    /// Put the fields returned by `parts` back together.
+    /// Error returns if the timestamp is more than 45 bits, the extension length is more than 15,
+    /// or the extension and the filler do not fit in `ext_len` and `15 - ext_len` bits.
+    pub fn from_parts(parts: Parts) -> Result<EUID, Error> {
+        if parts.timestamp > EUID::TIMESTAMP_BITMASK {
+            return Err(Error::TimestampOverflow(parts.timestamp));
+        }
+        let ext_len: u64 = parts.ext_len as u64;
+        if ext_len > EUID::EXT_LEN_BITMASK {
+            return Err(Error::ExtensionLengthOverflow(parts.ext_len));
+        }
+        if (parts.extension as u64) >> ext_len != 0 {
+            return Err(Error::ExtensionOverflow(parts.extension));
+        }
+        if (parts.filler as u64) >> (15 - ext_len) != 0 {
+            return Err(Error::FillerOverflow(parts.filler));
+        }
+        let hi: u64 = (parts.timestamp << 19)
+            | ((parts.filler as u64) << (4 + ext_len))
+            | ((parts.extension as u64) << 4)
+            | ext_len;
+        Ok(EUID(hi, ((parts.r_hi as u64) << 32) | parts.r_lo as u64))
+    }
+
     /// Derive monotonic EUID.
     ///
     /// This function generate sortable EUID, None returns if overflow happens when incrementing randomness.
+    /// If the clock is behind the timestamp of this EUID, the timestamp is kept and the randomness
+    /// is incremented (see [`ClockRegression::Hold`]).
+    #[cfg(feature = "std")]
     pub fn next(&self) -> Option<EUID> {
-        let timestamp: u64 = time::current_timestamp();
-        if timestamp == self.timestamp() {
-            let r_hi = self.1 >> 32;
-            if r_hi == 0xffffffff {
-                None
-            } else {
-                Some(EUID(
-                    self.0,
-                    ((r_hi + 1) << 32) | random::random_u32() as u64,
-                ))
-            }
-        } else {
-            match self.extension() {
-                Some(ext) => EUID::create_with_timestamp_and_extension(timestamp, ext),
-                None => EUID::create_with_timestamp(timestamp),
-            }
-        }
+        self.try_next().ok()
+    }
+
+    /// Derive monotonic EUID, returning the reason of the failure.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::{Error, EUID};
+    ///
+    /// let euid: EUID = EUID::create().unwrap_or_default();
+    /// match euid.try_next() {
+    ///     Ok(next) => assert!(euid < next),
+    ///     Err(Error::CounterOverflow) => println!("randomness overflow, try again later"),
+    ///     Err(e) => panic!("{}", e),
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn try_next(&self) -> Result<EUID, Error> {
+        Generator::new(SystemClock, PooledRandom).try_next(self)
     }
 
     /// Encode EUID to string Base-32 string.
@@ -155,12 +543,142 @@ impl EUID {
     /// println!("{}", euid.encode(true)); // with check-mod.
     /// println!("{}", euid.encode(false)); // without check-mod.
     /// ```
-    pub fn encode(&self, checkmod: bool) -> String {
-        base32::encode(self, checkmod)
+    #[cfg(feature = "alloc")]
+    pub fn encode(&self, checkmod: bool) -> alloc::string::String {
+        alloc::string::String::from(self.encoded(checkmod).as_str())
+    }
+
+    /// Encode EUID to string with the given case, check-mod and grouping.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::{EncodeOptions, EUID};
+    ///
+    /// let euid: EUID = EUID::create().unwrap_or_default();
+    /// let options: EncodeOptions = EncodeOptions::new().with_group_size(5);
+    /// let grouped: String = euid.encode_with(&options);
+    /// assert_eq!(Ok(euid), options.decode(&grouped));
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn encode_with(&self, options: &EncodeOptions) -> alloc::string::String {
+        options.encode(self)
+    }
+
+    /// Encode EUID into `buf` without allocating, returns the encoded part of `buf`.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::EUID;
+    ///
+    /// let euid: EUID = EUID::create().unwrap_or_default();
+    /// let mut buf: [u8; 27] = [0u8; 27];
+    /// assert_eq!(euid.encode(true), euid.encode_to(&mut buf, true));
+    /// ```
+    pub fn encode_to<'a>(&self, buf: &'a mut [u8; 27], checkmod: bool) -> &'a str {
+        base32::encode_to(self, checkmod, buf)
+    }
+
+    /// Encode EUID into a stack allocated [`EncodedEuid`].
+    pub fn encoded(&self, checkmod: bool) -> EncodedEuid {
+        EncodedEuid::new(self, checkmod)
+    }
+
+    /// Decode EUID entered or pasted by a human.
+    ///
+    /// Before decoding, surrounding whitespace is trimmed, a `urn:`-style prefix (everything
+    /// up to the last colon) is stripped, separators (whitespace, hyphens, underscores, dots)
+    /// are dropped and full-width or Cyrillic/Greek look-alike characters are folded to ASCII.
+    /// The error carries the byte offset of the offending character in `input` and the
+    /// normalization that was applied. Use `EUID::from_str` for machine-generated input.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::EUID;
+    ///
+    /// let euid: EUID = EUID::parse_lenient(" urn:euid:C8ZM1-4GR4J-XG0MQ-XVY18-S8TJN-A6\n").unwrap();
+    /// assert_eq!("C8ZM14GR4JXG0MQXVY18S8TJNA6", euid.to_string());
+    /// ```
+    pub fn parse_lenient(input: &str) -> Result<EUID, LenientError> {
+        lenient::parse_lenient(input)
+    }
+
+    /// Suggest corrections for a mistyped EUID, most likely first.
+    ///
+    /// Tries single symbol substitutions (commonly confused symbols such as `U`/`V`, `5`/`S`
+    /// or `8`/`B` first), adjacent transpositions and, if `input` is one symbol short or
+    /// long, single insertions or deletions. Only candidates with a verified check-mod are
+    /// returned, an EUID encoded without check-mod can't be corrected. Returns no
+    /// candidates if `input` already decodes with a verified check-mod.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::{Edit, EUID};
+    ///
+    /// let corrections = EUID::suggest_corrections("C8ZM14GR4JXG0MQXUY18S8TJNA6");
+    /// assert_eq!("C8ZM14GR4JXG0MQXVY18S8TJNA6", corrections[0].euid.to_string());
+    /// assert_eq!(Edit::Confusion { position: 16, from: 'U', to: 'V' }, corrections[0].edit);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn suggest_corrections(input: &str) -> alloc::vec::Vec<Correction> {
+        correct::suggest_corrections(input)
+    }
+
+    /// Decode EUID like `EUID::from_str` does, and report what was accepted beyond the
+    /// canonical form: a missing check-mod, lowercase letters or `O`, `I`, `L` aliases.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::EUID;
+    ///
+    /// let (euid, report) = EUID::decode_with_report("c8zm14gr4jxgomqxvyl8s8tjna6").unwrap();
+    /// assert_eq!("C8ZM14GR4JXG0MQXVY18S8TJNA6", euid.encoded(true).as_str());
+    /// assert!(report.checkmod);
+    /// assert!(report.aliases_normalized && report.lowercase_normalized);
+    /// assert!(!report.is_canonical());
+    /// ```
+    pub fn decode_with_report(encoded: &str) -> Result<(EUID, DecodeReport), Error> {
+        base32::decode_with_report(encoded)
+    }
+
+    /// Decode EUID in canonical form only: uppercase, without `O`, `I`, `L` aliases and
+    /// with a verified check-mod. Use it for security sensitive input, where two spellings
+    /// of the same EUID must not both be accepted.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::{Error, EUID};
+    ///
+    /// assert!(EUID::decode_strict("C8ZM14GR4JXG0MQXVY18S8TJNA6").is_ok());
+    /// assert_eq!(
+    ///     Err(Error::NonCanonicalCharacter('O', 12)),
+    ///     EUID::decode_strict("C8ZM14GR4JXGOMQXVY18S8TJNA6")
+    /// );
+    /// assert_eq!(
+    ///     Err(Error::MissingCheckmod),
+    ///     EUID::decode_strict("C8ZM14GR4JXG0MQXVY18S8TJNBZ")
+    /// );
+    /// ```
+    pub fn decode_strict(encoded: &str) -> Result<EUID, Error> {
+        base32::decode_strict(encoded)
+    }
+
+    /// Decode EUID from ASCII bytes, without UTF-8 validation.
+    /// Same as `EUID::from_str`, except that [`Error::InvalidCharacter`] carries the
+    /// offending byte as `char`.
+    ///
+    /// Example:
+    /// ```rust
+    /// use euid::EUID;
+    ///
+    /// let euid: EUID = EUID::decode_bytes(b"C8ZM14GR4JXG0MQXVY18S8TJNBZ").unwrap();
+    /// assert_eq!("C8ZM14GR4JXG0MQXVY18S8TJNBZ", euid.encode(false));
+    /// ```
+    pub const fn decode_bytes(encoded: &[u8]) -> Result<EUID, Error> {
+        base32::decode_bytes(encoded)
     }
 
     #[inline(always)]
-    fn get_ext_bit_len(ext: u16) -> u64 {
+    pub(crate) fn get_ext_bit_len(ext: u16) -> u64 {
         let mut x: u16 = ext & 0x7fff;
         let mut n: u64 = 0;
         if x <= 0x00ff {
@@ -181,40 +699,89 @@ impl EUID {
         16 - n
     }
 
-    #[inline(always)]
+    #[cfg(all(test, feature = "std"))]
     fn create_with_timestamp(timestamp: u64) -> Option<EUID> {
+        EUID::create_with_timestamp_from(timestamp, &mut PooledRandom).ok()
+    }
+
+    #[cfg(all(test, feature = "std"))]
+    fn create_with_timestamp_and_extension(timestamp: u64, extension: u16) -> Option<EUID> {
+        EUID::create_with_timestamp_and_extension_from(timestamp, extension, &mut PooledRandom).ok()
+    }
+
+    #[inline(always)]
+    pub(crate) fn create_with_timestamp_from<R: RandomSource + ?Sized>(
+        timestamp: u64,
+        random: &mut R,
+    ) -> Result<EUID, Error> {
         if timestamp > EUID::TIMESTAMP_BITMASK {
-            None
+            Err(Error::TimestampOverflow(timestamp))
         } else {
-            let (r0, r1) = random::random_u128();
-            Some(EUID((timestamp << 19) | ((r0 & 0x7fff) << 4), r1))
+            let (r0, r1) = random.try_random_u128()?;
+            Ok(EUID((timestamp << 19) | ((r0 & 0x7fff) << 4), r1))
         }
     }
 
     #[inline(always)]
-    fn create_with_timestamp_and_extension(timestamp: u64, extension: u16) -> Option<EUID> {
+    pub(crate) fn create_with_timestamp_and_extension_from<R: RandomSource + ?Sized>(
+        timestamp: u64,
+        extension: u16,
+        random: &mut R,
+    ) -> Result<EUID, Error> {
         if timestamp > EUID::TIMESTAMP_BITMASK {
-            None
+            Err(Error::TimestampOverflow(timestamp))
         } else {
             let ext_data: u64 = extension as u64;
             if ext_data > EUID::EXT_DATA_BITMASK {
-                None
+                Err(Error::ExtensionOverflow(extension))
             } else {
                 let ext_len: u64 = EUID::get_ext_bit_len(extension);
-                let (r0, r1) = random::random_u128();
+                let (r0, r1) = random.try_random_u128()?;
                 let remain_rand: u64 = r0 & ((1 << (15 - ext_len)) - 1);
                 let hi: u64 =
                     (timestamp << 19) | (remain_rand << (4 + ext_len)) | (ext_data << 4) | ext_len;
-                Some(EUID(hi, r1))
+                Ok(EUID(hi, r1))
             }
         }
     }
+
+    /// Create random EUID with the given timestamp and the extension of this EUID.
+    #[inline(always)]
+    pub(crate) fn renew_from<R: RandomSource + ?Sized>(
+        &self,
+        timestamp: u64,
+        random: &mut R,
+    ) -> Result<EUID, Error> {
+        match self.extension() {
+            Some(ext) => EUID::create_with_timestamp_and_extension_from(timestamp, ext, random),
+            None => EUID::create_with_timestamp_from(timestamp, random),
+        }
+    }
+
+    /// Increment the high part of randomness and refill the low part, keeping the header.
+    /// Error returns if the high part of randomness is already at its maximum.
+    #[inline(always)]
+    pub(crate) fn increment_from<R: RandomSource + ?Sized>(
+        &self,
+        random: &mut R,
+    ) -> Result<EUID, Error> {
+        let r_hi = self.1 >> 32;
+        if r_hi == 0xffffffff {
+            Err(Error::CounterOverflow)
+        } else {
+            Ok(EUID(
+                self.0,
+                ((r_hi + 1) << 32) | random.try_random_u32()? as u64,
+            ))
+        }
+    }
 }
 
-impl std::fmt::Display for EUID {
+impl core::fmt::Display for EUID {
     /// Encode to lexicographically sortable string.
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.encode(true))
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let mut buf: [u8; 27] = [0u8; 27];
+        f.write_str(base32::encode_to(self, true, &mut buf))
     }
 }
 
@@ -226,13 +793,11 @@ impl From<EUID> for u128 {
 
 impl From<u128> for EUID {
     fn from(value: u128) -> EUID {
-        let hi: u64 = (value >> 64) as u64;
-        let lo: u64 = (value & 0xffffffffffffffff) as u64;
-        EUID(hi, lo)
+        EUID::from_u128(value)
     }
 }
 
-impl std::str::FromStr for EUID {
+impl core::str::FromStr for EUID {
     type Err = Error;
 
     /// Parse string representation of EUID.
@@ -252,16 +817,30 @@ impl PartialEq for EUID {
 
 impl Eq for EUID {}
 
-impl std::hash::Hash for EUID {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+impl core::hash::Hash for EUID {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.0.hash(state);
         self.1.hash(state);
     }
 }
 
 impl Ord for EUID {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        if self.0 != other.0 {
+            return if self.0 > other.0 {
+                core::cmp::Ordering::Greater
+            } else {
+                core::cmp::Ordering::Less
+            };
+        }
+        if self.1 != other.1 {
+            return if self.1 > other.1 {
+                core::cmp::Ordering::Greater
+            } else {
+                core::cmp::Ordering::Less
+            };
+        }
+        core::cmp::Ordering::Equal
     }
 }
 
@@ -289,52 +868,37 @@ impl From<EUID> for [u8; 16] {
         v[5] = ((value.0 >> 16) & 0xff) as u8;
         v[6] = ((value.0 >> 8) & 0xff) as u8;
         v[7] = (value.0 & 0xff) as u8;
-        v[8] = ((value.0 >> 56) & 0xff) as u8;
-        v[9] = ((value.0 >> 48) & 0xff) as u8;
-        v[10] = ((value.0 >> 40) & 0xff) as u8;
-        v[11] = ((value.0 >> 32) & 0xff) as u8;
-        v[12] = ((value.0 >> 24) & 0xff) as u8;
-        v[13] = ((value.0 >> 16) & 0xff) as u8;
-        v[14] = ((value.0 >> 8) & 0xff) as u8;
-        v[15] = (value.0 & 0xff) as u8;
+        v[8] = ((value.1 >> 56) & 0xff) as u8;
+        v[9] = ((value.1 >> 48) & 0xff) as u8;
+        v[10] = ((value.1 >> 40) & 0xff) as u8;
+        v[11] = ((value.1 >> 32) & 0xff) as u8;
+        v[12] = ((value.1 >> 24) & 0xff) as u8;
+        v[13] = ((value.1 >> 16) & 0xff) as u8;
+        v[14] = ((value.1 >> 8) & 0xff) as u8;
+        v[15] = (value.1 & 0xff) as u8;
         v
     }
 }
 
 impl PartialOrd for EUID {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self.0 != other.0 {
-            return Some(if self.0 > other.0 {
-                std::cmp::Ordering::Greater
-            } else {
-                std::cmp::Ordering::Less
-            });
-        }
-        if self.1 != other.1 {
-            return Some(if self.1 > other.1 {
-                std::cmp::Ordering::Greater
-            } else {
-                std::cmp::Ordering::Less
-            });
-        }
-        Some(std::cmp::Ordering::Equal)
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 #[cfg(test)]
 mod tests {
 
+    #[cfg(feature = "std")]
     use std::hash::Hasher;
+    #[cfg(feature = "std")]
     use std::str::FromStr;
 
+    #[cfg(feature = "std")]
     use rand::{seq::SliceRandom, thread_rng};
 
     fn get_timestamp_diff(start: u64, timestamp: u64) -> u64 {
-        if start < timestamp {
-            timestamp - start
-        } else {
-            start - timestamp
-        }
+        start.abs_diff(timestamp)
     }
 
     fn get_ext_bit_len0(v: u16) -> u64 {
@@ -354,6 +918,7 @@ mod tests {
         assert_eq!(1, get_timestamp_diff(2, 1));
     }
 
+    #[cfg(feature = "std")]
     fn normalize_timestamp(now: u64, epoch: u64) -> u64 {
         if epoch < now {
             now - epoch
@@ -362,6 +927,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     fn get_timestamp_from_epoch(epoch: u64) -> u64 {
         let duration: Result<std::time::Duration, std::time::SystemTimeError> =
             std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH);
@@ -375,6 +941,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn create_with_timestamp_and_extension_test() {
         assert_eq!(
@@ -425,6 +992,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn create_test() {
         let now: u64 = std::time::SystemTime::now()
@@ -438,6 +1006,7 @@ mod tests {
         assert_eq!(None, euid.extension());
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn create_with_extension_test() {
         for i in 0u64..crate::EUID::EXT_DATA_BITMASK {
@@ -457,6 +1026,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn conversion_test() {
         for i in 0..crate::EUID::EXT_DATA_BITMASK {
@@ -489,11 +1059,12 @@ mod tests {
             crate::EUID::from_str("C8754X9NN8H80X298KRKERG8K888")
         );
         assert_eq!(
-            Err(crate::Error::InvalidCharacter('U')),
+            Err(crate::Error::InvalidCharacter('U', 25)),
             crate::EUID::from_str("C8754X9NN8H80X298KRKERG8KU8")
         );
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn monotonic_test() {
         let hi: u64 = crate::EUID::create().unwrap().0;
@@ -519,6 +1090,86 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn batch_test() {
+        let euids: Vec<crate::EUID> = crate::EUID::create_batch_with_extension(0x7fff, 5).unwrap();
+        assert_eq!(0x7fff, euids.len());
+        for i in 1..euids.len() {
+            assert!(euids[i - 1] < euids[i]);
+            assert_eq!(Some(5), euids[i].extension());
+        }
+        assert_eq!(
+            None,
+            crate::EUID::create_batch_with_extension(1, 0x7fff + 1)
+        );
+
+        let mut euids: Vec<crate::EUID> = vec![crate::EUID::default(); 1000];
+        assert_eq!(Ok(()), crate::EUID::fill(&mut euids));
+        let mut ordered: Vec<crate::EUID> = euids.clone();
+        ordered.shuffle(&mut thread_rng());
+        ordered.sort();
+        assert_eq!(euids, ordered);
+
+        let euids: Vec<crate::EUID> = crate::EUID::iter_with_extension(1).take(1000).collect();
+        for i in 1..euids.len() {
+            assert!(euids[i - 1] < euids[i]);
+            assert_eq!(Some(1), euids[i].extension());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn error_test() {
+        assert_eq!(
+            Err(crate::Error::ExtensionOverflow(0x8000)),
+            crate::EUID::try_create_with_extension(0x8000)
+        );
+        assert_eq!(
+            Err(crate::Error::ExtensionOverflow(0x8000)),
+            crate::EUID::try_create_batch_with_extension(2, 0x8000)
+        );
+        // Ahead of the clock, so the millisecond can not roll over before `try_next`.
+        let future: crate::EUID =
+            crate::EUID::create_with_timestamp(crate::time::current_timestamp() + 60000).unwrap();
+        let overflowed: crate::EUID = crate::EUID(future.0, u64::MAX);
+        assert_eq!(Err(crate::Error::CounterOverflow), overflowed.try_next());
+
+        let err: Box<dyn std::error::Error> =
+            Box::new(crate::EUID::from_str("C8754X9NN8H80X298KRKERG8KU8").unwrap_err());
+        assert_eq!("invalid character 'U' at byte offset 25", err.to_string());
+        assert_eq!(
+            "invalid length 25, expected 27",
+            crate::Error::InvalidLength(25, 27).to_string()
+        );
+        assert_eq!(
+            "timestamp 35184372088832 is more than 45 bits",
+            crate::Error::TimestampOverflow(crate::EUID::TIMESTAMP_BITMASK + 1).to_string()
+        );
+        assert_eq!(
+            "non-canonical character 'o' at byte offset 12",
+            crate::Error::NonCanonicalCharacter('o', 12).to_string()
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn const_test() {
+        const EUID: crate::EUID = crate::euid!("C8ZM14GR4JXG0MQXVY18S8TJNBZ");
+        const DECODED: Result<crate::EUID, crate::Error> =
+            crate::EUID::decode_bytes(b"C8X2HA87098A0W837DX13FEAWVV");
+        assert_eq!(
+            crate::EUID::from_str("C8ZM14GR4JXG0MQXVY18S8TJNBZ"),
+            Ok(EUID)
+        );
+        assert_eq!(Err(crate::Error::InvalidCheckmod(123, 56)), DECODED);
+        for _ in 0..1000 {
+            let euid: crate::EUID = crate::EUID::create().unwrap();
+            assert_eq!(euid, crate::EUID::from_u128(u128::from(euid)));
+        }
+    }
+
+    #[cfg(feature = "std")]
     #[test]
     fn bytes_test() {
         let euid: crate::EUID = crate::EUID::create().unwrap_or_default();
@@ -528,6 +1179,15 @@ mod tests {
         assert_eq!(euid, from_bytes);
     }
 
+    #[test]
+    fn bytes_order_test() {
+        let value: u128 = 0x0123456789abcdeffedcba9876543210;
+        let bytes: [u8; 16] = From::from(crate::EUID::from(value));
+        assert_eq!(value.to_be_bytes(), bytes);
+        assert_eq!(crate::EUID::from(value), crate::EUID::from(bytes));
+    }
+
+    #[cfg(feature = "std")]
     #[test]
     fn hash_test() {
         let euid: crate::EUID = crate::EUID::create().unwrap_or_default();
@@ -538,6 +1198,20 @@ mod tests {
         assert_eq!(default_hasher0.finish(), default_hasher1.finish());
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn datetime_test() {
+        let euid: crate::EUID = crate::EUID::create().unwrap_or_default();
+        let millis: u64 = euid
+            .datetime()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        assert_eq!(euid.timestamp(), millis);
+        assert_eq!(euid.timestamp(), euid.unix_timestamp(crate::Epoch::UNIX));
+    }
+
+    #[cfg(feature = "std")]
     #[test]
     fn print_test() {
         let euid: crate::EUID = crate::EUID::create().unwrap_or_default();
@@ -0,0 +1,429 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{Clock, Epoch, EUID};
+
+/// Whether an EUID must carry an extension.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ExtensionRule {
+    /// With or without extension (default).
+    #[default]
+    Any,
+    /// Extension required.
+    Required,
+    /// Extension not allowed.
+    Forbidden,
+}
+
+/// Reason a [`ValidationPolicy`] rejects an EUID.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Violation {
+    /// Created before the accepted time window (milliseconds since the Unix epoch).
+    TooOld { unix_millis: u64, not_before: u64 },
+    /// Created after the accepted time window (milliseconds since the Unix epoch).
+    TooFarInFuture { unix_millis: u64, not_after: u64 },
+    /// Extension required, but there is none.
+    MissingExtension,
+    /// Extension forbidden, but there is one.
+    UnexpectedExtension(u16),
+    /// Extension outside of the allowed range.
+    ExtensionOutOfRange(u16),
+    /// Extension length is not the bit length of the extension, as EUIDs created by this
+    /// crate have.
+    ExtensionLengthMismatch { ext_len: u8, expected: u8 },
+}
+
+impl core::fmt::Display for Violation {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Violation::TooOld {
+                unix_millis,
+                not_before,
+            } => write!(f, "created at {}, before {}", unix_millis, not_before),
+            Violation::TooFarInFuture {
+                unix_millis,
+                not_after,
+            } => write!(f, "created at {}, after {}", unix_millis, not_after),
+            Violation::MissingExtension => write!(f, "missing extension"),
+            Violation::UnexpectedExtension(ext) => write!(f, "unexpected extension {}", ext),
+            Violation::ExtensionOutOfRange(ext) => write!(f, "extension {} is out of range", ext),
+            Violation::ExtensionLengthMismatch { ext_len, expected } => {
+                write!(f, "extension length {}, expected {}", ext_len, expected)
+            }
+        }
+    }
+}
+
+/// Violations found by [`ValidationPolicy::validate_at`], kept on the stack.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Violations {
+    violations: [Option<Violation>; Violations::CAPACITY],
+    len: usize,
+}
+
+impl Violations {
+    /// Most violations a single EUID can have: time window, extension rule, extension
+    /// range and extension length.
+    pub const CAPACITY: usize = 4;
+
+    fn push(&mut self, violation: Violation) {
+        self.violations[self.len] = Some(violation);
+        self.len += 1;
+    }
+
+    /// Returns the number of violations.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no violations.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if `violation` is one of the violations.
+    pub fn contains(&self, violation: &Violation) -> bool {
+        self.iter().any(|v| v == violation)
+    }
+
+    /// Returns an iterator over the violations, in the order they were checked.
+    pub fn iter(&self) -> impl Iterator<Item = &Violation> {
+        self.violations[..self.len].iter().flatten()
+    }
+}
+
+impl core::fmt::Display for Violations {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        for (i, violation) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Violations {}
+
+/// Plausibility checks for decoded EUIDs, to reject forged or garbage IDs early.
+///
+/// Any 128-bit value decodes, so `EUID::from_str` and `From<[u8; 16]>` accept timestamp 0
+/// or timestamps centuries ahead. The default policy accepts every EUID, each `with_*`
+/// method adds a check.
+///
+/// Example:
+/// ```rust
+/// use euid::{ExtensionRule, ManualClock, ValidationPolicy, Violation, EUID};
+///
+/// const POLICY: ValidationPolicy = ValidationPolicy::new()
+///     .with_not_before(1700000000000)
+///     .with_max_future(5 * 60 * 1000)
+///     .with_extension_rule(ExtensionRule::Required)
+///     .with_extension_range(1, 99);
+///
+/// let clock: ManualClock = ManualClock::new(1800000000000);
+/// let euid: EUID = "C8ZM14GR4JXG0MQXVY18S8TJNA6".parse().unwrap();
+/// let violations = POLICY.validate_at(&euid, &clock).unwrap_err();
+/// assert!(violations.contains(&Violation::MissingExtension));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ValidationPolicy {
+    epoch: Epoch,
+    not_before: Option<u64>,
+    max_future: Option<u64>,
+    extension_rule: ExtensionRule,
+    extension_range: Option<(u16, u16)>,
+    ext_len_check: bool,
+}
+
+impl Default for ValidationPolicy {
+    fn default() -> Self {
+        ValidationPolicy::new()
+    }
+}
+
+impl ValidationPolicy {
+    /// Create policy accepting every EUID.
+    pub const fn new() -> Self {
+        ValidationPolicy {
+            epoch: Epoch::UNIX,
+            not_before: None,
+            max_future: None,
+            extension_rule: ExtensionRule::Any,
+            extension_range: None,
+            ext_len_check: false,
+        }
+    }
+
+    /// Set the epoch the EUIDs were created with (default: the Unix epoch).
+    pub const fn with_epoch(mut self, epoch: Epoch) -> Self {
+        self.epoch = epoch;
+        self
+    }
+
+    /// Reject EUIDs created before `unix_millis` milliseconds since the Unix epoch,
+    /// e.g. the launch of the system.
+    pub const fn with_not_before(mut self, unix_millis: u64) -> Self {
+        self.not_before = Some(unix_millis);
+        self
+    }
+
+    /// Reject EUIDs created more than `millis` milliseconds after the current time of the
+    /// clock, to allow for clock skew between systems.
+    pub const fn with_max_future(mut self, millis: u64) -> Self {
+        self.max_future = Some(millis);
+        self
+    }
+
+    /// Set whether an extension is required or forbidden (default: [`ExtensionRule::Any`]).
+    pub const fn with_extension_rule(mut self, rule: ExtensionRule) -> Self {
+        self.extension_rule = rule;
+        self
+    }
+
+    /// Reject extensions outside of `min..=max`. EUIDs without extension are left to the
+    /// extension rule.
+    pub const fn with_extension_range(mut self, min: u16, max: u16) -> Self {
+        self.extension_range = Some((min, max));
+        self
+    }
+
+    /// Reject EUIDs whose extension length is not the bit length of the extension
+    /// (extension 0 has length 1), which this crate never creates.
+    pub const fn with_ext_len_check(mut self, check: bool) -> Self {
+        self.ext_len_check = check;
+        self
+    }
+
+    /// Returns the epoch the EUIDs were created with.
+    pub const fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    /// Returns the earliest accepted creation time, in milliseconds since the Unix epoch.
+    pub const fn not_before(&self) -> Option<u64> {
+        self.not_before
+    }
+
+    /// Returns how far in the future of the clock an EUID may be created, in milliseconds.
+    pub const fn max_future(&self) -> Option<u64> {
+        self.max_future
+    }
+
+    /// Returns whether an extension is required or forbidden.
+    pub const fn extension_rule(&self) -> ExtensionRule {
+        self.extension_rule
+    }
+
+    /// Returns the allowed extension range, inclusive.
+    pub const fn extension_range(&self) -> Option<(u16, u16)> {
+        self.extension_range
+    }
+
+    /// Returns whether the extension length is checked.
+    pub const fn ext_len_check(&self) -> bool {
+        self.ext_len_check
+    }
+
+    /// Validate `euid` against the system clock.
+    #[cfg(feature = "std")]
+    pub fn validate(&self, euid: &EUID) -> Result<(), Violations> {
+        self.validate_at(euid, &crate::SystemClock)
+    }
+
+    /// Validate `euid`, the time window is relative to the current time of `clock`.
+    pub fn validate_at<C: Clock>(&self, euid: &EUID, clock: &C) -> Result<(), Violations> {
+        let mut violations: Violations = Violations::default();
+        let unix_millis: u64 = euid.unix_timestamp(self.epoch);
+        match (self.not_before, self.max_future) {
+            (Some(not_before), _) if unix_millis < not_before => {
+                violations.push(Violation::TooOld {
+                    unix_millis,
+                    not_before,
+                });
+            }
+            (_, Some(max_future)) => {
+                let not_after: u64 = clock.current_timestamp().saturating_add(max_future);
+                if unix_millis > not_after {
+                    violations.push(Violation::TooFarInFuture {
+                        unix_millis,
+                        not_after,
+                    });
+                }
+            }
+            _ => (),
+        }
+        let extension: Option<u16> = euid.extension();
+        match (self.extension_rule, extension) {
+            (ExtensionRule::Required, None) => violations.push(Violation::MissingExtension),
+            (ExtensionRule::Forbidden, Some(ext)) => {
+                violations.push(Violation::UnexpectedExtension(ext))
+            }
+            _ => (),
+        }
+        if let (Some((min, max)), Some(ext)) = (self.extension_range, extension) {
+            if ext < min || ext > max {
+                violations.push(Violation::ExtensionOutOfRange(ext));
+            }
+        }
+        if let (true, Some(ext)) = (self.ext_len_check, extension) {
+            let ext_len: u8 = (euid.0 & EUID::EXT_LEN_BITMASK) as u8;
+            let expected: u8 = EUID::get_ext_bit_len(ext) as u8;
+            if ext_len != expected {
+                violations.push(Violation::ExtensionLengthMismatch { ext_len, expected });
+            }
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        Epoch, EuidBuilder, ExtensionRule, ManualClock, ValidationPolicy, Violation, EUID,
+    };
+    use rand::Rng;
+
+    const LAUNCH: u64 = 1700000000000;
+    const NOW: u64 = 1800000000000;
+
+    fn euid(unix_millis: u64, ext_len: u8, extension: u16) -> EUID {
+        EUID::from_parts(crate::Parts {
+            timestamp: unix_millis,
+            ext_len,
+            extension,
+            filler: 0,
+            r_hi: 0,
+            r_lo: 0,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn validate_time_window_test() {
+        let clock: ManualClock = ManualClock::new(NOW);
+        let policy: ValidationPolicy = ValidationPolicy::new()
+            .with_not_before(LAUNCH)
+            .with_max_future(1000);
+        assert_eq!(Ok(()), policy.validate_at(&euid(LAUNCH, 0, 0), &clock));
+        assert_eq!(Ok(()), policy.validate_at(&euid(NOW + 1000, 0, 0), &clock));
+        let violations = policy.validate_at(&EUID::default(), &clock).unwrap_err();
+        assert_eq!(1, violations.len());
+        assert!(violations.contains(&Violation::TooOld {
+            unix_millis: 0,
+            not_before: LAUNCH
+        }));
+        let violations = policy
+            .validate_at(&euid(NOW + 1001, 0, 0), &clock)
+            .unwrap_err();
+        assert_eq!(
+            "created at 1800000001001, after 1800000001000",
+            violations.to_string()
+        );
+        clock.advance(1);
+        assert_eq!(Ok(()), policy.validate_at(&euid(NOW + 1001, 0, 0), &clock));
+
+        // Timestamps count from the epoch of the policy.
+        let epoch: ValidationPolicy = policy.with_epoch(Epoch::from_unix_millis(LAUNCH));
+        assert_eq!(Ok(()), epoch.validate_at(&EUID::default(), &clock));
+        assert!(epoch.validate_at(&euid(NOW, 0, 0), &clock).is_err());
+
+        assert_eq!(
+            Ok(()),
+            ValidationPolicy::new().validate_at(&EUID(u64::MAX, u64::MAX), &clock)
+        );
+    }
+
+    #[test]
+    fn validate_extension_test() {
+        let clock: ManualClock = ManualClock::new(NOW);
+        let required: ValidationPolicy = ValidationPolicy::new()
+            .with_extension_rule(ExtensionRule::Required)
+            .with_extension_range(10, 20)
+            .with_ext_len_check(true);
+        assert_eq!(Ok(()), required.validate_at(&euid(NOW, 4, 10), &clock));
+        assert_eq!(
+            Violation::MissingExtension,
+            *required
+                .validate_at(&euid(NOW, 0, 0), &clock)
+                .unwrap_err()
+                .iter()
+                .next()
+                .unwrap()
+        );
+        let violations = required
+            .validate_at(&euid(NOW, 15, 21), &clock)
+            .unwrap_err();
+        assert_eq!(
+            vec![
+                Violation::ExtensionOutOfRange(21),
+                Violation::ExtensionLengthMismatch {
+                    ext_len: 15,
+                    expected: 5
+                }
+            ],
+            violations.iter().copied().collect::<Vec<Violation>>()
+        );
+
+        let forbidden: ValidationPolicy =
+            ValidationPolicy::new().with_extension_rule(ExtensionRule::Forbidden);
+        assert_eq!(Ok(()), forbidden.validate_at(&euid(NOW, 0, 0), &clock));
+        assert!(forbidden
+            .validate_at(&euid(NOW, 1, 0), &clock)
+            .unwrap_err()
+            .contains(&Violation::UnexpectedExtension(0)));
+
+        let all: ValidationPolicy = forbidden
+            .with_not_before(LAUNCH)
+            .with_extension_range(1, 1)
+            .with_ext_len_check(true);
+        let violations = all.validate_at(&euid(0, 3, 0), &clock).unwrap_err();
+        assert_eq!(crate::Violations::CAPACITY, violations.len());
+    }
+
+    #[test]
+    fn validate_created_test() {
+        let clock: ManualClock = ManualClock::new(NOW);
+        let policy: ValidationPolicy = ValidationPolicy::new()
+            .with_not_before(NOW)
+            .with_max_future(0)
+            .with_extension_range(0, 0x7fff)
+            .with_ext_len_check(true);
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let created: EUID = EuidBuilder::new()
+                .with_timestamp(NOW)
+                .with_extension(rng.gen_range(0..0x8000))
+                .with_r_lo(rng.gen())
+                .build()
+                .unwrap();
+            assert_eq!(Ok(()), policy.validate_at(&created, &clock));
+        }
+    }
+}
@@ -20,9 +20,253 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-#[allow(unused_imports)]
-use getrandom::getrandom;
+use crate::Error;
 
+/// A source of random bits used to fill the random parts of EUIDs.
+///
+/// Implement this trait to plug in a deterministic, faster or hardware backed
+/// generator (see [`Generator`](crate::Generator)).
+pub trait RandomSource {
+    /// Returns 32 random bits.
+    fn random_u32(&mut self) -> u32;
+
+    /// Returns 128 random bits as a `(high, low)` pair.
+    fn random_u128(&mut self) -> (u64, u64);
+
+    /// Returns 32 random bits, or [`Error::EntropyUnavailable`] if the source failed.
+    /// Generators always draw through this method.
+    fn try_random_u32(&mut self) -> Result<u32, Error> {
+        Ok(self.random_u32())
+    }
+
+    /// Returns 128 random bits as a `(high, low)` pair, or [`Error::EntropyUnavailable`]
+    /// if the source failed. Generators always draw through this method.
+    fn try_random_u128(&mut self) -> Result<(u64, u64), Error> {
+        Ok(self.random_u128())
+    }
+}
+
+/// Random source that asks the operating system (`getrandom`) on every draw.
+///
+/// The infallible methods return zero bits if the operating system fails.
+#[cfg(feature = "getrandom")]
+#[derive(Default, Copy, Clone, Debug)]
+pub struct OsRandom;
+
+#[cfg(feature = "getrandom")]
+impl RandomSource for OsRandom {
+    fn random_u32(&mut self) -> u32 {
+        random_u32()
+    }
+
+    fn random_u128(&mut self) -> (u64, u64) {
+        random_u128()
+    }
+
+    fn try_random_u32(&mut self) -> Result<u32, Error> {
+        let mut r: [u8; 4] = [0u8; 4];
+        getrandom::getrandom(&mut r).map_err(|_| Error::EntropyUnavailable)?;
+        Ok(u32::from_be_bytes(r))
+    }
+
+    fn try_random_u128(&mut self) -> Result<(u64, u64), Error> {
+        let mut r: [u8; 16] = [0u8; 16];
+        getrandom::getrandom(&mut r).map_err(|_| Error::EntropyUnavailable)?;
+        Ok(to_u128(r))
+    }
+}
+
+/// Default random source: a thread-local pool of operating system entropy.
+///
+/// The pool is refilled from `getrandom` in chunks of 1024 bytes, so most draws
+/// do not make a system call. Bytes are wiped from the pool once handed out, and
+/// the pool is discarded in a child process after `fork()`, so parent and child
+/// never hand out the same bits.
+///
+/// The infallible methods return zero bits if the operating system fails.
+#[cfg(feature = "std")]
+#[derive(Default, Copy, Clone, Debug)]
+pub struct PooledRandom;
+
+#[cfg(feature = "std")]
+impl RandomSource for PooledRandom {
+    fn random_u32(&mut self) -> u32 {
+        self.try_random_u32().unwrap_or(0)
+    }
+
+    fn random_u128(&mut self) -> (u64, u64) {
+        self.try_random_u128().unwrap_or((0, 0))
+    }
+
+    fn try_random_u32(&mut self) -> Result<u32, Error> {
+        let mut r: [u8; 4] = [0u8; 4];
+        fill_from_pool(&mut r)?;
+        Ok(u32::from_be_bytes(r))
+    }
+
+    fn try_random_u128(&mut self) -> Result<(u64, u64), Error> {
+        let mut r: [u8; 16] = [0u8; 16];
+        fill_from_pool(&mut r)?;
+        Ok(to_u128(r))
+    }
+}
+
+#[cfg(feature = "std")]
+const POOL_SIZE: usize = 1024;
+
+#[cfg(feature = "std")]
+struct Pool {
+    buf: [u8; POOL_SIZE],
+    pos: usize,
+    generation: u64,
+}
+
+#[cfg(feature = "std")]
+impl Pool {
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        let generation: u64 = fork::generation();
+        if generation != self.generation {
+            self.pos = POOL_SIZE;
+            self.generation = generation;
+        }
+        let mut written: usize = 0;
+        while written < dest.len() {
+            if self.pos == POOL_SIZE {
+                getrandom::getrandom(&mut self.buf).map_err(|_| Error::EntropyUnavailable)?;
+                self.pos = 0;
+            }
+            let n: usize = (dest.len() - written).min(POOL_SIZE - self.pos);
+            let chunk: &mut [u8] = &mut self.buf[self.pos..self.pos + n];
+            dest[written..written + n].copy_from_slice(chunk);
+            chunk.iter_mut().for_each(|b| *b = 0);
+            self.pos += n;
+            written += n;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    static POOL: std::cell::RefCell<Pool> = const {
+        std::cell::RefCell::new(Pool {
+            buf: [0u8; POOL_SIZE],
+            pos: POOL_SIZE,
+            generation: 0,
+        })
+    };
+}
+
+#[cfg(feature = "std")]
+fn fill_from_pool(dest: &mut [u8]) -> Result<(), Error> {
+    match POOL.try_with(|pool| pool.borrow_mut().fill(dest)) {
+        Ok(result) => result,
+        // The pool of this thread is already destroyed.
+        Err(_) => getrandom::getrandom(dest).map_err(|_| Error::EntropyUnavailable),
+    }
+}
+
+#[cfg(all(feature = "std", unix))]
+mod fork {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static GENERATION: AtomicU64 = AtomicU64::new(0);
+    static REGISTER: std::sync::Once = std::sync::Once::new();
+
+    extern "C" fn on_fork_child() {
+        GENERATION.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of `fork()` calls this process descends from.
+    pub fn generation() -> u64 {
+        REGISTER.call_once(|| unsafe {
+            libc::pthread_atfork(None, None, Some(on_fork_child));
+        });
+        GENERATION.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(all(feature = "std", not(unix)))]
+mod fork {
+    /// Processes are never forked on this platform.
+    pub fn generation() -> u64 {
+        0
+    }
+}
+
+#[cfg(feature = "getrandom")]
+fn to_u128(r: [u8; 16]) -> (u64, u64) {
+    let n: u128 = u128::from_be_bytes(r);
+    ((n >> 64) as u64, (n & 0xffffffffffffffff) as u64)
+}
+
+/// Deterministic random source seeded by the caller, for reproducible tests and simulations.
+///
+/// The sequence is produced by SplitMix64 (Steele, Lea and Flood, "Fast Splittable
+/// Pseudorandom Number Generators", OOPSLA 2014) using only wrapping 64-bit arithmetic,
+/// so the same seed yields the same bits on every platform. `random_u32` returns the
+/// high 32 bits of the next output and `random_u128` returns two consecutive outputs.
+///
+/// **Not for production identifiers:** anyone who knows or guesses the seed can
+/// predict every EUID. Use [`OsRandom`] for identifiers that must be unguessable.
+///
+/// Example:
+/// ```rust
+/// use euid::{RandomSource, SeededRandom};
+///
+/// let mut a: SeededRandom = SeededRandom::new(42);
+/// let mut b: SeededRandom = SeededRandom::new(42);
+/// assert_eq!(a.random_u128(), b.random_u128());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SeededRandom(u64);
+
+impl SeededRandom {
+    /// Create random source from `seed`.
+    pub const fn new(seed: u64) -> SeededRandom {
+        SeededRandom(seed)
+    }
+
+    /// Returns the next 64-bit output of SplitMix64.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z: u64 = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl RandomSource for SeededRandom {
+    fn random_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn random_u128(&mut self) -> (u64, u64) {
+        let hi: u64 = self.next_u64();
+        (hi, self.next_u64())
+    }
+}
+
+impl<R: RandomSource + ?Sized> RandomSource for &mut R {
+    fn random_u32(&mut self) -> u32 {
+        (**self).random_u32()
+    }
+
+    fn random_u128(&mut self) -> (u64, u64) {
+        (**self).random_u128()
+    }
+
+    fn try_random_u32(&mut self) -> Result<u32, Error> {
+        (**self).try_random_u32()
+    }
+
+    fn try_random_u128(&mut self) -> Result<(u64, u64), Error> {
+        (**self).try_random_u128()
+    }
+}
+
+#[cfg(feature = "getrandom")]
 pub fn random_u32() -> u32 {
     let mut r: [u8; 4] = [0u8; 4];
     match getrandom::getrandom(&mut r) {
@@ -31,13 +275,11 @@ pub fn random_u32() -> u32 {
     }
 }
 
+#[cfg(feature = "getrandom")]
 pub fn random_u128() -> (u64, u64) {
     let mut r: [u8; 16] = [0u8; 16];
     match getrandom::getrandom(&mut r) {
-        Ok(_) => {
-            let n: u128 = u128::from_be_bytes(r);
-            ((n >> 64) as u64, (n & 0xffffffffffffffff) as u64)
-        }
+        Ok(_) => to_u128(r),
         Err(_) => (0, 0),
     }
 }
@@ -45,9 +287,105 @@ pub fn random_u128() -> (u64, u64) {
 #[cfg(test)]
 mod tests {
 
+    #[cfg(feature = "std")]
     #[test]
     fn random_test() {
         assert!((super::random_u32() as u64) < super::random_u128().0);
         assert!((super::random_u32() as u64) < super::random_u128().1);
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn os_random_test() {
+        use super::RandomSource;
+
+        let mut os: super::OsRandom = super::OsRandom;
+        assert_ne!(os.random_u128(), os.random_u128());
+        assert_ne!(os.random_u32(), os.random_u32());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn pooled_random_test() {
+        use super::RandomSource;
+
+        let mut pooled: super::PooledRandom = super::PooledRandom;
+        let mut seen: std::collections::HashSet<(u64, u64)> = std::collections::HashSet::new();
+        for _ in 0..(4 * super::POOL_SIZE) {
+            assert!(seen.insert(pooled.try_random_u128().unwrap()));
+        }
+        assert_ne!(pooled.random_u32(), pooled.random_u32());
+        let handle = std::thread::spawn(|| super::PooledRandom.try_random_u128().unwrap());
+        assert!(seen.insert(handle.join().unwrap()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn pool_wipe_test() {
+        let mut pool = super::Pool {
+            buf: [0u8; super::POOL_SIZE],
+            pos: super::POOL_SIZE,
+            generation: super::fork::generation(),
+        };
+        let mut r: [u8; super::POOL_SIZE + 7] = [0u8; super::POOL_SIZE + 7];
+        pool.fill(&mut r).unwrap();
+        assert_eq!(7, pool.pos);
+        assert!(pool.buf[..7].iter().all(|b| *b == 0));
+        assert!(pool.buf[7..].iter().any(|b| *b != 0));
+    }
+
+    #[cfg(all(feature = "std", unix))]
+    #[test]
+    fn pooled_random_fork_test() {
+        use super::RandomSource;
+
+        // Prime the pool so the child inherits a partially consumed buffer.
+        let _ = super::PooledRandom.try_random_u128().unwrap();
+        let mut fds: [libc::c_int; 2] = [0; 2];
+        assert_eq!(0, unsafe { libc::pipe(fds.as_mut_ptr()) });
+        let pid: libc::pid_t = unsafe { libc::fork() };
+        assert!(pid >= 0);
+        if pid == 0 {
+            // Child: no allocation, just draw and report.
+            let r: [u8; 16] = match super::PooledRandom.try_random_u128() {
+                Ok((hi, lo)) => (((hi as u128) << 64) | lo as u128).to_be_bytes(),
+                Err(_) => [0u8; 16],
+            };
+            unsafe {
+                libc::write(fds[1], r.as_ptr() as *const libc::c_void, 16);
+                libc::_exit(0);
+            }
+        }
+        let (hi, lo) = super::PooledRandom.try_random_u128().unwrap();
+        let mut r: [u8; 16] = [0u8; 16];
+        let n: isize = unsafe { libc::read(fds[0], r.as_mut_ptr() as *mut libc::c_void, 16) };
+        unsafe {
+            libc::waitpid(pid, std::ptr::null_mut(), 0);
+            libc::close(fds[0]);
+            libc::close(fds[1]);
+        }
+        assert_eq!(16, n);
+        assert_ne!([0u8; 16], r);
+        assert_ne!((((hi as u128) << 64) | lo as u128).to_be_bytes(), r);
+    }
+
+    #[test]
+    fn seeded_random_test() {
+        use super::RandomSource;
+
+        // Reference outputs of SplitMix64 for seed 1234567.
+        let mut seeded: super::SeededRandom = super::SeededRandom::new(1234567);
+        assert_eq!(6457827717110365317, seeded.next_u64());
+        assert_eq!(3203168211198807973, seeded.next_u64());
+        assert_eq!(9817491932198370423, seeded.next_u64());
+        assert_eq!(4593380528125082431, seeded.next_u64());
+        assert_eq!(16408922859458223821, seeded.next_u64());
+
+        let mut seeded: super::SeededRandom = super::SeededRandom::new(1234567);
+        assert_eq!((6457827717110365317u64 >> 32) as u32, seeded.random_u32());
+        assert_eq!(
+            (3203168211198807973, 9817491932198370423),
+            seeded.random_u128()
+        );
+    }
 }
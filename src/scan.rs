@@ -0,0 +1,446 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{base32, EncodeOptions, EUID};
+use core::ops::Range;
+
+/// Where and how [`scan`] looks for EUIDs in text.
+///
+/// By default only whole words are matched, in the canonical form (any case) with a
+/// verified check-mod: without check-mod any 27 symbols long token would match.
+///
+/// Example:
+/// ```rust
+/// use euid::{EncodeOptions, ScanOptions, EUID};
+///
+/// const OPTIONS: ScanOptions = ScanOptions::new()
+///     .with_grouped(EncodeOptions::new().with_group_size(5));
+///
+/// let text: &str = "ticket C8ZM1-4GR4J-XG0MQ-XVY18-S8TJN-A6 reopened";
+/// let found: Vec<_> = OPTIONS.scan(text).collect();
+/// assert_eq!(1, found.len());
+/// assert_eq!(7..39, found[0].0);
+/// assert_eq!(Ok(found[0].1), "C8ZM14GR4JXG0MQXVY18S8TJNA6".parse::<EUID>());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ScanOptions {
+    word_boundary: bool,
+    require_checkmod: bool,
+    grouped: Option<EncodeOptions>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions::new()
+    }
+}
+
+impl ScanOptions {
+    /// Create options matching whole words with a verified check-mod.
+    pub const fn new() -> Self {
+        ScanOptions {
+            word_boundary: true,
+            require_checkmod: true,
+            grouped: None,
+        }
+    }
+
+    /// Set whether an EUID must be a whole word, not preceded or followed by an ASCII
+    /// letter, digit or underscore (default: true).
+    pub const fn with_word_boundary(mut self, word_boundary: bool) -> Self {
+        self.word_boundary = word_boundary;
+        self
+    }
+
+    /// Set whether to skip EUIDs without check-mod (default: true).
+    pub const fn with_require_checkmod(mut self, require_checkmod: bool) -> Self {
+        self.require_checkmod = require_checkmod;
+        self
+    }
+
+    /// Also match EUIDs grouped like `options` encodes them, e.g. `C8ZM1-4GR4J-...`.
+    /// Only the separator and group size of `options` are used.
+    pub const fn with_grouped(mut self, options: EncodeOptions) -> Self {
+        self.grouped = Some(options);
+        self
+    }
+
+    /// Returns whether EUIDs must be whole words.
+    pub const fn word_boundary(&self) -> bool {
+        self.word_boundary
+    }
+
+    /// Returns whether EUIDs without check-mod are skipped.
+    pub const fn require_checkmod(&self) -> bool {
+        self.require_checkmod
+    }
+
+    /// Returns the grouped form matched besides the canonical one.
+    pub const fn grouped(&self) -> Option<EncodeOptions> {
+        self.grouped
+    }
+
+    /// Returns an iterator over the byte ranges and EUIDs found in `text`.
+    pub fn scan<'a>(&self, text: &'a str) -> Scan<'a> {
+        Scan {
+            text: text.as_bytes(),
+            pos: 0,
+            options: *self,
+        }
+    }
+
+    /// Returns an iterator over the EUIDs found in `reader`, read through a window of
+    /// bounded size, with byte ranges counted from the start of the stream. The stream
+    /// need not be UTF-8 nor contain newlines.
+    #[cfg(feature = "std")]
+    pub fn scan_reader<R: std::io::BufRead>(&self, reader: R) -> ScanReader<R> {
+        ScanReader {
+            reader,
+            window: std::vec::Vec::new(),
+            offset: 0,
+            pos: 0,
+            eof: false,
+            options: *self,
+        }
+    }
+
+    fn is_boundary(&self, text: &[u8], at: usize) -> bool {
+        !self.word_boundary
+            || !matches!(text.get(at), Some(b) if b.is_ascii_alphanumeric() || *b == b'_')
+    }
+
+    /// Decode the symbols of `symbols`, if they qualify.
+    fn accept(&self, symbols: &[u8; 27]) -> Option<EUID> {
+        match base32::decode_bytes(symbols) {
+            Ok(euid) if !self.require_checkmod || base32::has_checkmod(symbols) => Some(euid),
+            _ => None,
+        }
+    }
+
+    /// Match an EUID at `start` of `text`, in the canonical or the grouped form.
+    fn match_at(&self, text: &[u8], start: usize) -> Option<(Range<usize>, EUID)> {
+        let mut symbols: [u8; 27] = [0u8; 27];
+        if let Some(window) = text.get(start..start + 27) {
+            if window.iter().all(|&b| base32::is_symbol(b)) {
+                symbols.copy_from_slice(window);
+                if self.is_boundary(text, start + 27) {
+                    if let Some(euid) = self.accept(&symbols) {
+                        return Some((start..start + 27, euid));
+                    }
+                }
+            }
+        }
+        let grouped: EncodeOptions = self.grouped?;
+        let end: usize = start + grouped.encoded_len();
+        let mut n: usize = 0;
+        let mut expect_separator: bool = false;
+        for &b in text.get(start..end)? {
+            if expect_separator {
+                if b != grouped.separator() as u8 {
+                    return None;
+                }
+                expect_separator = false;
+                continue;
+            }
+            if !base32::is_symbol(b) {
+                return None;
+            }
+            symbols[n] = b;
+            n += 1;
+            expect_separator = n < 27 && n.checked_rem(grouped.group_size()) == Some(0);
+        }
+        if !self.is_boundary(text, end) {
+            return None;
+        }
+        self.accept(&symbols).map(|euid| (start..end, euid))
+    }
+
+    /// Find the first EUID in `text` starting in `starts`.
+    fn find(&self, text: &[u8], starts: Range<usize>) -> Option<(Range<usize>, EUID)> {
+        starts
+            .filter(|&start| start == 0 || self.is_boundary(text, start - 1))
+            .find_map(|start| self.match_at(text, start))
+    }
+}
+
+/// Find the EUIDs with a verified check-mod in `text`, matched as whole words.
+/// Returns an iterator over their byte ranges in `text` and the decoded EUIDs,
+/// see [`ScanOptions`] for other forms.
+///
+/// Example:
+/// ```rust
+/// let text: &str = "user C8ZM14GR4JXG0MQXVY18S8TJNA6 logged in, token C8ZM14GR4JXG0MQXVY18S8TJNA7";
+/// let found: Vec<_> = euid::scan(text).collect();
+/// assert_eq!(1, found.len());
+/// assert_eq!("C8ZM14GR4JXG0MQXVY18S8TJNA6", &text[found[0].0.clone()]);
+/// ```
+pub fn scan(text: &str) -> Scan<'_> {
+    ScanOptions::new().scan(text)
+}
+
+/// Iterator returned by [`scan`] and [`ScanOptions::scan`].
+#[derive(Clone, Debug)]
+pub struct Scan<'a> {
+    text: &'a [u8],
+    pos: usize,
+    options: ScanOptions,
+}
+
+impl Iterator for Scan<'_> {
+    type Item = (Range<usize>, EUID);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (range, euid) = self.options.find(self.text, self.pos..self.text.len())?;
+        self.pos = range.end;
+        Some((range, euid))
+    }
+}
+
+/// Bytes [`ScanReader`] adds to its window per read at most.
+#[cfg(feature = "std")]
+const CHUNK: usize = 8 * 1024;
+
+/// Iterator returned by [`ScanOptions::scan_reader`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct ScanReader<R> {
+    reader: R,
+    window: std::vec::Vec<u8>,
+    offset: u64,
+    pos: usize,
+    eof: bool,
+    options: ScanOptions,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead> Iterator for ScanReader<R> {
+    type Item = std::io::Result<(Range<u64>, EUID)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // A match at a start is only decided once the longest form and the byte after
+            // it, which decides the word boundary, are in the window.
+            let decided: usize = if self.eof {
+                self.window.len()
+            } else {
+                self.window.len().saturating_sub(EncodeOptions::MAX_LEN)
+            };
+            if let Some((range, euid)) = self.options.find(&self.window, self.pos..decided) {
+                self.pos = range.end;
+                let start: u64 = self.offset + range.start as u64;
+                let end: u64 = self.offset + range.end as u64;
+                return Some(Ok((start..end, euid)));
+            }
+            if self.eof {
+                return None;
+            }
+            // Carry the undecided starts and the byte before them over to the next read.
+            let next: usize = self.pos.max(decided);
+            let carry_from: usize = next.saturating_sub(1);
+            self.window.drain(..carry_from);
+            self.offset += carry_from as u64;
+            self.pos = next - carry_from;
+            let n: usize = match self.reader.fill_buf() {
+                Ok([]) => {
+                    self.eof = true;
+                    0
+                }
+                Ok(buf) => {
+                    let n: usize = buf.len().min(CHUNK);
+                    self.window.extend_from_slice(&buf[..n]);
+                    n
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => 0,
+                Err(e) => return Some(Err(e)),
+            };
+            self.reader.consume(n);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{EncodeOptions, ScanOptions, EUID};
+    use rand::Rng;
+    use std::ops::Range;
+
+    const CANONICAL: &str = "C8ZM14GR4JXG0MQXVY18S8TJNA6";
+
+    fn ranges<I: Iterator<Item = (Range<usize>, EUID)>>(found: I) -> Vec<Range<usize>> {
+        found.map(|(range, _)| range).collect()
+    }
+
+    #[test]
+    fn scan_test() {
+        let euid: EUID = CANONICAL.parse().unwrap();
+        assert_eq!(
+            vec![(0..27, euid)],
+            crate::scan(CANONICAL).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(3..30, euid)],
+            crate::scan("id=c8zm14gr4jxg0mqxvy18s8tjna6;").collect::<Vec<_>>()
+        );
+        // Wrong or missing check-mod.
+        assert_eq!(0, crate::scan("C8ZM14GR4JXG0MQXVY18S8TJNA7").count());
+        assert_eq!(0, crate::scan("C8ZM14GR4JXG0MQXVY18S8TJNBZ").count());
+        assert_eq!(
+            vec![0..27],
+            ranges(
+                ScanOptions::new()
+                    .with_require_checkmod(false)
+                    .scan("C8ZM14GR4JXG0MQXVY18S8TJNBZ")
+            )
+        );
+
+        let glued: &str = "xC8ZM14GR4JXG0MQXVY18S8TJNA6 C8ZM14GR4JXG0MQXVY18S8TJNA6_";
+        assert_eq!(0, crate::scan(glued).count());
+        let anywhere: ScanOptions = ScanOptions::new().with_word_boundary(false);
+        assert_eq!(vec![1..28, 29..56], ranges(anywhere.scan(glued)));
+        // Every substring is tried, not only the ones at a boundary.
+        assert_eq!(
+            vec![3..30],
+            ranges(anywhere.scan("AAAC8ZM14GR4JXG0MQXVY18S8TJNA6"))
+        );
+
+        let grouped: ScanOptions =
+            ScanOptions::new().with_grouped(EncodeOptions::new().with_group_size(5));
+        let text: &str = "a C8ZM1-4GR4J-XG0MQ-XVY18-S8TJN-A6, b C8ZM14GR4JXG0MQXVY18S8TJNA6.";
+        assert_eq!(vec![2..34, 38..65], ranges(grouped.scan(text)));
+        assert_eq!(vec![38..65], ranges(crate::scan(text)));
+        assert_eq!(0, grouped.scan("C8ZM1-4GR4J-XG0MQ-XVY18-S8TJ-NA6").count());
+    }
+
+    #[test]
+    fn scan_random_test() {
+        let mut rng = rand::thread_rng();
+        let mut text: String = String::new();
+        let mut expected: Vec<(Range<usize>, EUID)> = Vec::new();
+        for _ in 0..1000 {
+            let euid: EUID = EUID::from(rng.gen::<u128>());
+            let start: usize = text.len();
+            text.push_str(&euid.encoded(true));
+            expected.push((start..text.len(), euid));
+            // Noise: plain words, non-ASCII words or a word of 28 symbols.
+            match rng.gen_range(0..3) {
+                0 => text.push_str(" token "),
+                1 => text.push_str(", ünïcödé\n"),
+                _ => text.push_str(" 0000000000000000000000000000 "),
+            }
+        }
+        assert_eq!(expected, crate::scan(&text).collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn scan_reader_test() {
+        let mut rng = rand::thread_rng();
+        let mut log: Vec<u8> = Vec::new();
+        for i in 0..1000 {
+            log.extend_from_slice(format!("{} INFO request ", i).as_bytes());
+            if rng.gen() {
+                log.extend_from_slice(&EUID::from(rng.gen::<u128>()).encoded(true).as_bytes()[..]);
+            }
+            // Invalid UTF-8 does not stop the scan.
+            log.extend_from_slice(b" \xff done\n");
+        }
+        let text: String = String::from_utf8_lossy(&log).into_owned();
+        let found: Vec<(Range<u64>, EUID)> = ScanOptions::new()
+            .scan_reader(std::io::Cursor::new(&log))
+            .collect::<std::io::Result<_>>()
+            .unwrap();
+        let lossy: Vec<EUID> = crate::scan(&text).map(|(_, euid)| euid).collect();
+        assert_eq!(
+            lossy,
+            found.iter().map(|(_, euid)| *euid).collect::<Vec<_>>()
+        );
+        for (range, euid) in found {
+            let bytes: &[u8] = &log[range.start as usize..range.end as usize];
+            assert_eq!(Ok(euid), EUID::decode_bytes(bytes));
+        }
+    }
+
+    /// Hands out one byte per read.
+    #[cfg(feature = "std")]
+    struct ByteReader<'a>(&'a [u8]);
+
+    #[cfg(feature = "std")]
+    impl std::io::Read for ByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n: usize = buf.len().min(self.0.len().min(1));
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::io::BufRead for ByteReader<'_> {
+        fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+            Ok(&self.0[..self.0.len().min(1)])
+        }
+
+        fn consume(&mut self, amt: usize) {
+            self.0 = &self.0[amt..];
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn scan_reader_window_test() {
+        let mut rng = rand::thread_rng();
+        let options: ScanOptions = ScanOptions::new()
+            .with_word_boundary(rng.gen())
+            .with_grouped(EncodeOptions::new().with_group_size(1));
+        let mut text: String = String::new();
+        for _ in 0..1000 {
+            let euid: EUID = EUID::from(rng.gen::<u128>());
+            match rng.gen_range(0..3) {
+                0 => text.push_str(&euid.encoded(true)),
+                1 => text.push_str(&options.grouped().unwrap().encode(&euid)),
+                _ => text.push_str("0000000000000000000000000000"),
+            }
+            text.push_str([" ", "x", "-", ""][rng.gen_range(0..4)]);
+        }
+        let expected: Vec<(Range<u64>, EUID)> = options
+            .scan(&text)
+            .map(|(range, euid)| (range.start as u64..range.end as u64, euid))
+            .collect();
+        assert!(!expected.is_empty());
+
+        let mut reader = options.scan_reader(ByteReader(text.as_bytes()));
+        let mut found: Vec<(Range<u64>, EUID)> = Vec::new();
+        while let Some(next) = reader.next() {
+            found.push(next.unwrap());
+            assert!(reader.window.len() <= EncodeOptions::MAX_LEN + 2);
+        }
+        assert!(reader.window.len() <= EncodeOptions::MAX_LEN + 2);
+        assert_eq!(expected, found);
+        // The default buffered reader fills bigger windows.
+        let found: Vec<(Range<u64>, EUID)> = options
+            .scan_reader(std::io::BufReader::new(text.as_bytes()))
+            .collect::<std::io::Result<_>>()
+            .unwrap();
+        assert_eq!(expected, found);
+    }
+}
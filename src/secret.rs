@@ -0,0 +1,336 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::siphash::wipe;
+use crate::{Error, EUID};
+
+/// Number of folds that reduce any 128-bit value below 2 * 127, each fold removes at
+/// least 6 bits.
+const FOLDS: usize = 24;
+
+/// Counts the steps of the constant-time loops, to test they don't depend on the data.
+#[cfg(test)]
+mod steps {
+    std::thread_local!(static STEPS: core::cell::Cell<usize> = const { core::cell::Cell::new(0) });
+
+    pub fn step() {
+        STEPS.with(|steps| steps.set(steps.get() + 1));
+    }
+
+    pub fn take() -> usize {
+        STEPS.with(|steps| steps.replace(0))
+    }
+}
+
+#[cfg(not(test))]
+mod steps {
+    #[inline(always)]
+    pub fn step() {}
+}
+
+/// EUID used as a secret, e.g. an unguessable share-link token.
+///
+/// Equality and decoding run in constant time: no early returns and no table lookups
+/// indexed by secret data, so their timing only depends on the input length. There is
+/// no `Display`, `Hash` or ordering, `Debug` is redacted, and there is neither `Clone`
+/// nor an all-zero `Default`; the value is overwritten on drop. Use [`SecretEuid::expose_secret`] to get the EUID, e.g. to encode it once
+/// when issuing the token.
+///
+/// Example:
+/// ```rust
+/// use euid::SecretEuid;
+///
+/// let issued: SecretEuid = "C8ZM14GR4JXG0MQXVY18S8TJNA6".parse().unwrap();
+/// let presented: SecretEuid = SecretEuid::decode("C8ZM14GR4JXG0MQXVY18S8TJNA6").unwrap();
+/// assert!(issued == presented);
+/// assert_eq!("SecretEuid(..)", format!("{:?}", issued));
+/// ```
+pub struct SecretEuid(EUID);
+
+impl SecretEuid {
+    /// Wrap `euid`.
+    pub fn new(euid: EUID) -> SecretEuid {
+        SecretEuid(euid)
+    }
+
+    /// Returns the wrapped EUID, out of constant-time protection.
+    pub fn expose_secret(&self) -> &EUID {
+        &self.0
+    }
+
+    /// Compare in constant time.
+    pub fn ct_eq(&self, other: &SecretEuid) -> bool {
+        let diff: u64 = (self.0 .0 ^ other.0 .0) | (self.0 .1 ^ other.0 .1);
+        // 1 if diff is zero, without branching on it.
+        let zero: u64 = 1 ^ ((diff | diff.wrapping_neg()) >> 63);
+        zero == 1
+    }
+
+    /// Decode in constant time, accepts what `EUID::from_str` accepts.
+    ///
+    /// Every symbol is decoded and the check-mod computed regardless of errors. Only the
+    /// length and, once decoding is done, whether and why it failed affect the timing.
+    pub fn decode(encoded: &str) -> Result<SecretEuid, Error> {
+        let bytes: &[u8] = encoded.as_bytes();
+        if bytes.len() != 27 {
+            return Err(Error::InvalidLength(bytes.len(), 27));
+        }
+        let mut n: u128 = 0;
+        let mut check: u32 = 0;
+        let mut invalid_at: u32 = 27;
+        for (i, &b) in bytes.iter().enumerate() {
+            steps::step();
+            let (value, valid) = symbol_value(b);
+            // Offset of the first invalid symbol, or 27.
+            let keep: u32 = valid.wrapping_neg() | ct_lt(invalid_at, 27).wrapping_neg();
+            invalid_at = select(keep, invalid_at, i as u32);
+            let v: u128 = value as u128;
+            // 125 bits from the first 25 symbols, 3 from the 26th, the rest is check-mod.
+            let full: u128 = ct_lt(i as u32, 25) as u128;
+            let last: u128 = ct_eq(i as u32, 25) as u128;
+            n = select128(full, n, (n << 5) | v);
+            n = select128(last, n, (n << 3) | (v >> 2));
+            check = select(
+                ct_lt(i as u32, 25).wrapping_neg(),
+                check,
+                (check << 5) | value,
+            );
+        }
+        check &= 0x7f;
+        let expected: u32 = m7(n);
+        let checkmod_ok: u32 = ct_eq(check, 0x7f) | ct_eq(check, expected);
+        let euid: EUID = EUID((n >> 64) as u64, n as u64);
+        if invalid_at < 27 {
+            let offset: usize = invalid_at as usize;
+            let c: char = encoded[offset..].chars().next().unwrap_or_default();
+            return Err(Error::InvalidCharacter(c, offset));
+        }
+        if checkmod_ok == 0 {
+            return Err(Error::InvalidCheckmod(check as usize, expected as usize));
+        }
+        Ok(SecretEuid(euid))
+    }
+}
+
+/// Returns 1 if `a == b`, 0 otherwise.
+fn ct_eq(a: u32, b: u32) -> u32 {
+    let diff: u32 = a ^ b;
+    1 ^ ((diff | diff.wrapping_neg()) >> 31)
+}
+
+/// Returns 1 if `a < b`, 0 otherwise, for values below 2^31.
+fn ct_lt(a: u32, b: u32) -> u32 {
+    a.wrapping_sub(b) >> 31
+}
+
+/// Returns 1 if `lo <= b <= hi`, 0 otherwise.
+fn ct_in(b: u32, lo: u32, hi: u32) -> u32 {
+    (1 ^ ct_lt(b, lo)) & (1 ^ ct_lt(hi, b))
+}
+
+/// Returns all ones if `bit` is 0, zero if `bit` is 1 (a mask keeping the old value).
+fn mask(bit: u32) -> u32 {
+    bit.wrapping_sub(1)
+}
+
+/// Returns `a` where `keep` is all ones, `b` where it is zero.
+fn select(keep: u32, a: u32, b: u32) -> u32 {
+    (a & keep) | (b & !keep)
+}
+
+/// Returns `b` if `take` is 1, `a` if it is 0.
+fn select128(take: u128, a: u128, b: u128) -> u128 {
+    let keep: u128 = take.wrapping_sub(1);
+    (a & keep) | (b & !keep)
+}
+
+/// Symbol value of `b` and 1 if it is a symbol, by arithmetic instead of a table lookup.
+fn symbol_value(b: u8) -> (u32, u32) {
+    let b: u32 = b as u32;
+    // Fold lowercase to uppercase.
+    let b: u32 = b - (ct_in(b, b'a' as u32, b'z' as u32) << 5);
+    let mut value: u32 = 0;
+    let mut valid: u32 = 0;
+    // (first, last, value of first)
+    let ranges: [(u8, u8, u32); 9] = [
+        (b'0', b'9', 0),
+        (b'A', b'H', 10),
+        (b'I', b'I', 1),
+        (b'J', b'K', 18),
+        (b'L', b'L', 1),
+        (b'M', b'N', 20),
+        (b'O', b'O', 0),
+        (b'P', b'T', 22),
+        (b'V', b'Z', 27),
+    ];
+    for (first, last, base) in ranges {
+        let hit: u32 = ct_in(b, first as u32, last as u32);
+        value |= hit.wrapping_neg() & b.wrapping_sub(first as u32).wrapping_add(base);
+        valid |= hit;
+    }
+    (value, valid)
+}
+
+/// Same as `check::m7`, with a fixed number of folds.
+fn m7(n: u128) -> u32 {
+    let mut i: u128 = n;
+    for _ in 0..FOLDS {
+        steps::step();
+        i = (i & 0x7f) + (i >> 7);
+    }
+    let i: u32 = i as u32;
+    i & mask(ct_eq(i, 0x7f))
+}
+
+impl From<EUID> for SecretEuid {
+    fn from(euid: EUID) -> Self {
+        SecretEuid(euid)
+    }
+}
+
+impl PartialEq for SecretEuid {
+    /// Constant-time, see [`SecretEuid::ct_eq`].
+    fn eq(&self, other: &SecretEuid) -> bool {
+        self.ct_eq(other)
+    }
+}
+
+impl Eq for SecretEuid {}
+
+impl core::str::FromStr for SecretEuid {
+    type Err = Error;
+
+    /// Constant-time, see [`SecretEuid::decode`].
+    fn from_str(encoded: &str) -> Result<SecretEuid, Self::Err> {
+        SecretEuid::decode(encoded)
+    }
+}
+
+impl core::fmt::Debug for SecretEuid {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("SecretEuid(..)")
+    }
+}
+
+impl Drop for SecretEuid {
+    fn drop(&mut self) {
+        wipe(&mut self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::steps;
+    use crate::{Error, SecretEuid, EUID};
+    use rand::Rng;
+
+    #[test]
+    fn secret_decode_test() {
+        assert_eq!(
+            Ok(SecretEuid::new(
+                "C8ZM14GR4JXG0MQXVY18S8TJNA6".parse().unwrap()
+            )),
+            SecretEuid::decode("c8zm14gr4jxgomqxvyl8s8tjna6")
+        );
+        assert_eq!(
+            Err(Error::InvalidCheckmod(71, 70)),
+            SecretEuid::decode("C8ZM14GR4JXG0MQXVY18S8TJNA7")
+        );
+        assert_eq!(
+            Err(Error::InvalidCharacter('U', 3)),
+            SecretEuid::decode("C8ZU14GR4JXG0MQXVY18S8TJNA6")
+        );
+        assert_eq!(
+            Err(Error::InvalidCharacter('é', 12)),
+            SecretEuid::decode("C8EE934SR007é5Q94QKKXFRFV8")
+        );
+        assert_eq!(
+            Err(Error::InvalidLength(26, 27)),
+            SecretEuid::decode("C8ZM14GR4JXG0MQXVY18S8TJNA")
+        );
+        let mut rng = rand::thread_rng();
+        for _ in 0..10000 {
+            let euid: EUID = EUID::from(rng.gen::<u128>());
+            let encoded = euid.encoded(rng.gen());
+            assert_eq!(
+                Ok(&euid),
+                SecretEuid::decode(&encoded)
+                    .as_ref()
+                    .map(|s| s.expose_secret())
+            );
+            assert_eq!(crate::check::m7(&euid) as u32, super::m7(u128::from(euid)));
+            // Same result as the table driven decoder for any bytes.
+            let mut bytes: [u8; 27] = *encoded.as_bytes();
+            bytes[rng.gen_range(0..27)] = rng.gen_range(0..0x80);
+            let encoded: &str = core::str::from_utf8(&bytes).unwrap();
+            assert_eq!(
+                EUID::decode_bytes(&bytes),
+                SecretEuid::decode(encoded).map(|s| *s.expose_secret())
+            );
+        }
+    }
+
+    #[test]
+    fn secret_constant_time_test() {
+        let inputs: [&str; 5] = [
+            "C8ZM14GR4JXG0MQXVY18S8TJNA6",
+            "c8zm14gr4jxgomqxvyl8s8tjna6",
+            "C8ZM14GR4JXG0MQXVY18S8TJNA7",
+            "U8ZM14GR4JXG0MQXVY18S8TJNA6",
+            "!!!!!!!!!!!!!!!!!!!!!!!!!!!",
+        ];
+        for input in inputs {
+            steps::take();
+            let _ = SecretEuid::decode(input);
+            // Every symbol and every fold, whether and wherever decoding fails.
+            assert_eq!(27 + super::FOLDS, steps::take());
+        }
+        // Fewer folds can leave a value at or above 2 * 127.
+        let mut i: u128 = u128::MAX;
+        for _ in 0..super::FOLDS {
+            i = (i & 0x7f) + (i >> 7);
+        }
+        assert!(i <= 0x7f);
+    }
+
+    #[test]
+    fn secret_eq_test() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let a: u128 = rng.gen();
+            let b: u128 = a ^ (1 << rng.gen_range(0..128));
+            assert!(SecretEuid::from(EUID::from(a)) == SecretEuid::from(EUID::from(a)));
+            assert!(SecretEuid::from(EUID::from(a)) != SecretEuid::from(EUID::from(b)));
+        }
+        let secret: SecretEuid = SecretEuid::from(EUID::from(u128::MAX));
+        assert_eq!("SecretEuid(..)", format!("{:?}", secret));
+    }
+
+    #[test]
+    fn drop_test() {
+        let euid: EUID = EUID(u64::MAX, u64::MAX);
+        let mut secret = core::mem::ManuallyDrop::new(SecretEuid::new(euid));
+        unsafe { core::mem::ManuallyDrop::drop(&mut secret) };
+        assert_eq!(EUID::default(), secret.0);
+    }
+}
@@ -0,0 +1,357 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::siphash::{key_halves, siphash24, wipe};
+use crate::{Clock, Error, Generator, RandomSource, EUID};
+
+/// Creates and verifies EUIDs carrying a truncated SipHash-2-4 MAC, to reject forged
+/// EUIDs without a lookup.
+///
+/// The low bits of the random part are replaced by a key id ([`EuidSigner::KEY_ID_BITS`]
+/// bits) and the MAC (`mac_bits`, 32 by default) over the rest of the EUID: timestamp,
+/// extension, filler, remaining random bits and key id. A signed EUID is still a normal
+/// EUID, it encodes, decodes and sorts by time like any other, though not by creation
+/// order within the same millisecond.
+///
+/// A forged EUID is accepted with probability 2^-`mac_bits` per attempt, at the cost of
+/// as many random bits:
+///
+/// | `mac_bits`    | forgery accepted with probability | random bits left (no extension) |
+/// |---------------|-----------------------------------|---------------------------------|
+/// | 16            | 1 in 65,536                       | 59                              |
+/// | 24            | 1 in 16.8 million                 | 51                              |
+/// | 32 (default)  | 1 in 4.3 billion                  | 43                              |
+/// | 48            | 1 in 2.8 * 10^14                  | 27                              |
+/// | 60            | 1 in 1.2 * 10^18                  | 15                              |
+///
+/// Keys rotate by signing with a new key id while still verifying with the previous ones.
+///
+/// A signer can not be cloned and is configured in place, share one by reference or
+/// `Arc`; its keys are overwritten on drop. Moving a signer may still leave a copy of
+/// the keys behind.
+///
+/// Example:
+/// ```rust
+/// use euid::{EuidSigner, EUID};
+///
+/// let mut signer: EuidSigner = EuidSigner::new(0, *b"0123456789abcdef");
+/// let issued: EUID = signer.create().unwrap();
+///
+/// // Rotate: sign with key 1, still accept key 0.
+/// signer.set_signing_key(1, *b"fedcba9876543210");
+/// assert!(signer.verify(&issued));
+/// assert!(signer.verify(&signer.create().unwrap()));
+///
+/// let forged: EUID = EUID::from(u128::from(issued) ^ (1 << 100));
+/// assert!(!signer.verify(&forged));
+/// assert!(!signer.remove_key(0).verify(&issued));
+/// ```
+pub struct EuidSigner {
+    keys: [Option<(u64, u64)>; 1 << EuidSigner::KEY_ID_BITS],
+    key_id: u8,
+    mac_bits: u32,
+}
+
+impl EuidSigner {
+    /// Number of key id bits, for up to 16 keys.
+    pub const KEY_ID_BITS: u32 = 4;
+
+    /// Create signer signing and verifying with `key`, identified by `key_id`.
+    ///
+    /// Panics if `key_id` is not below 16.
+    pub fn new(key_id: u8, key: [u8; 16]) -> Self {
+        let mut signer: EuidSigner = EuidSigner {
+            keys: [None; 1 << EuidSigner::KEY_ID_BITS],
+            key_id: 0,
+            mac_bits: 32,
+        };
+        signer.set_signing_key(key_id, key);
+        signer
+    }
+
+    /// Sign with `key` from now on, identified by `key_id`. Other keys still verify.
+    ///
+    /// Panics if `key_id` is not below 16.
+    pub fn set_signing_key(&mut self, key_id: u8, key: [u8; 16]) -> &mut Self {
+        self.add_key(key_id, key);
+        self.key_id = key_id;
+        self
+    }
+
+    /// Verify with `key` too, identified by `key_id`, e.g. the key before a rotation.
+    ///
+    /// Panics if `key_id` is not below 16.
+    pub fn add_key(&mut self, key_id: u8, key: [u8; 16]) -> &mut Self {
+        assert!(
+            key_id < 1 << EuidSigner::KEY_ID_BITS,
+            "key id must be below 16"
+        );
+        self.keys[key_id as usize] = Some(key_halves(&key));
+        self
+    }
+
+    /// Stop verifying with the key identified by `key_id`, once all EUIDs signed with it
+    /// expired. Removing the signing key makes `sign` and `create` fail with
+    /// [`Error::MissingSigningKey`] until another signing key is set.
+    pub fn remove_key(&mut self, key_id: u8) -> &mut Self {
+        if let Some(key) = self.keys.get_mut(key_id as usize) {
+            wipe(key);
+        }
+        self
+    }
+
+    /// Set the number of MAC bits (default: 32).
+    ///
+    /// Panics if `mac_bits` is not within 8..=60.
+    pub fn set_mac_bits(&mut self, mac_bits: u32) -> &mut Self {
+        assert!(
+            (8..=60).contains(&mac_bits),
+            "mac bits must be within 8..=60"
+        );
+        self.mac_bits = mac_bits;
+        self
+    }
+
+    /// Returns the id of the signing key.
+    pub fn key_id(&self) -> u8 {
+        self.key_id
+    }
+
+    /// Returns the number of MAC bits.
+    pub fn mac_bits(&self) -> u32 {
+        self.mac_bits
+    }
+
+    /// Returns the id of the key `euid` claims to be signed with.
+    pub fn key_id_of(&self, euid: &EUID) -> u8 {
+        ((euid.1 >> self.mac_bits) & ((1 << EuidSigner::KEY_ID_BITS) - 1)) as u8
+    }
+
+    fn mac_mask(&self) -> u64 {
+        (1 << self.mac_bits) - 1
+    }
+
+    /// MAC over the EUID without its MAC bits, the MAC length and the key id, so MACs of
+    /// different lengths or keys are unrelated.
+    fn mac(&self, key: (u64, u64), euid: &EUID) -> u64 {
+        let mut message: [u8; 18] = [0u8; 18];
+        message[..8].copy_from_slice(&euid.0.to_le_bytes());
+        message[8..16].copy_from_slice(&(euid.1 & !self.mac_mask()).to_le_bytes());
+        message[16] = self.mac_bits as u8;
+        message[17] = self.key_id_of(euid);
+        siphash24(key.0, key.1, &message) & self.mac_mask()
+    }
+
+    /// Replace the low random bits of `euid` by the signing key id and the MAC.
+    /// Error returns if the signing key was removed.
+    pub fn sign(&self, euid: &EUID) -> Result<EUID, Error> {
+        let key: (u64, u64) =
+            self.keys[self.key_id as usize].ok_or(Error::MissingSigningKey(self.key_id))?;
+        let key_id_mask: u64 = ((1 << EuidSigner::KEY_ID_BITS) - 1) << self.mac_bits;
+        let lo: u64 =
+            (euid.1 & !(key_id_mask | self.mac_mask())) | ((self.key_id as u64) << self.mac_bits);
+        let unsigned: EUID = EUID(euid.0, lo);
+        Ok(EUID(euid.0, lo | self.mac(key, &unsigned)))
+    }
+
+    /// Create signed EUID, or None if the system clock or the entropy fails, or the
+    /// signing key was removed.
+    #[cfg(feature = "std")]
+    pub fn create(&self) -> Option<EUID> {
+        self.try_create().ok()
+    }
+
+    /// Create signed EUID, see [`EuidSigner::create`].
+    #[cfg(feature = "std")]
+    pub fn try_create(&self) -> Result<EUID, Error> {
+        EUID::try_create().and_then(|euid| self.sign(&euid))
+    }
+
+    /// Create signed EUID with `extension`.
+    #[cfg(feature = "std")]
+    pub fn try_create_with_extension(&self, extension: u16) -> Result<EUID, Error> {
+        EUID::try_create_with_extension(extension).and_then(|euid| self.sign(&euid))
+    }
+
+    /// Create signed EUID from `generator`, e.g. without `std`.
+    pub fn try_create_from<C: Clock, R: RandomSource>(
+        &self,
+        generator: &mut Generator<C, R>,
+    ) -> Result<EUID, Error> {
+        generator.try_create().and_then(|euid| self.sign(&euid))
+    }
+
+    /// Returns true if `euid` carries a valid MAC for one of the keys.
+    /// The MAC comparison is constant-time.
+    pub fn verify(&self, euid: &EUID) -> bool {
+        match self.keys[self.key_id_of(euid) as usize] {
+            Some(key) => {
+                let diff: u64 = (euid.1 & self.mac_mask()) ^ self.mac(key, euid);
+                (diff | diff.wrapping_neg()) >> 63 == 0
+            }
+            None => false,
+        }
+    }
+}
+
+impl Drop for EuidSigner {
+    fn drop(&mut self) {
+        wipe(&mut self.keys);
+    }
+}
+
+impl core::fmt::Debug for EuidSigner {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("EuidSigner")
+            .field("key_id", &self.key_id)
+            .field("mac_bits", &self.mac_bits)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{Error, EuidSigner, Generator, ManualClock, EUID};
+    use rand::Rng;
+
+    const KEY: [u8; 16] = *b"0123456789abcdef";
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn sign_verify_test() {
+        let mut rng = rand::thread_rng();
+        for mac_bits in [8, 16, 32, 48, 60] {
+            let mut signer: EuidSigner = EuidSigner::new(3, KEY);
+            signer.set_mac_bits(mac_bits);
+            for _ in 0..1000 {
+                let euid: EUID = signer.create().unwrap();
+                assert!(signer.verify(&euid));
+                assert_eq!(3, signer.key_id_of(&euid));
+                // Survives the round trip through text.
+                let decoded: EUID = euid.encode(true).parse().unwrap();
+                assert!(signer.verify(&decoded));
+                // Signing a signed EUID changes nothing.
+                assert_eq!(Ok(euid), signer.sign(&euid));
+                // A flipped bit goes unnoticed with probability 2^-mac_bits.
+                let flipped: EUID = EUID::from(u128::from(euid) ^ (1 << rng.gen_range(0..128)));
+                if mac_bits >= 32 {
+                    assert!(!signer.verify(&flipped));
+                }
+            }
+        }
+        let euid: EUID = EuidSigner::new(0, KEY)
+            .try_create_with_extension(42)
+            .unwrap();
+        assert_eq!(Some(42), euid.extension());
+        assert!(EuidSigner::new(0, KEY).verify(&euid));
+        assert!(!EuidSigner::new(0, *b"0123456789abcdeF").verify(&euid));
+    }
+
+    #[test]
+    fn forgery_test() {
+        // Forgery probability 2^-8 with 8 bits of MAC.
+        let mut signer: EuidSigner = EuidSigner::new(0, KEY);
+        signer.set_mac_bits(8);
+        let mut rng = rand::thread_rng();
+        let attempts: usize = 256 * 1000;
+        let accepted: usize = (0..attempts)
+            .filter(|_| signer.verify(&EUID::from(rng.gen::<u128>() & !(0xf << 8))))
+            .count();
+        assert!(accepted > 800 && accepted < 1200);
+    }
+
+    #[test]
+    fn key_rotation_test() {
+        let mut generator = Generator::seeded(ManualClock::new(1700000000000), 7);
+        let mut signer: EuidSigner = EuidSigner::new(0, KEY);
+        let issued: EUID = signer.try_create_from(&mut generator).unwrap();
+        signer.set_signing_key(1, *b"fedcba9876543210");
+        let rotated: EUID = signer.try_create_from(&mut generator).unwrap();
+        assert_eq!(1, signer.key_id());
+        assert_eq!(1, signer.key_id_of(&rotated));
+        assert!(signer.verify(&issued));
+        assert!(signer.verify(&rotated));
+        signer.remove_key(0);
+        assert!(!signer.verify(&issued));
+        assert!(signer.verify(&rotated));
+        assert_eq!(
+            "EuidSigner { key_id: 1, mac_bits: 32, .. }",
+            format!("{:?}", signer)
+        );
+    }
+
+    #[test]
+    fn missing_signing_key_test() {
+        let mut generator = Generator::seeded(ManualClock::new(1700000000000), 7);
+        let mut signer: EuidSigner = EuidSigner::new(0, KEY);
+        signer.add_key(1, *b"fedcba9876543210");
+        let issued: EUID = signer.try_create_from(&mut generator).unwrap();
+        signer.remove_key(0);
+        assert_eq!(Err(Error::MissingSigningKey(0)), signer.sign(&issued));
+        assert_eq!(
+            Err(Error::MissingSigningKey(0)),
+            signer.try_create_from(&mut generator)
+        );
+        #[cfg(feature = "std")]
+        {
+            assert_eq!(Err(Error::MissingSigningKey(0)), signer.try_create());
+            assert_eq!(
+                Err(Error::MissingSigningKey(0)),
+                signer.try_create_with_extension(42)
+            );
+            assert_eq!(None, signer.create());
+        }
+        assert!(!signer.verify(&issued));
+        assert_eq!(
+            "signing key 0 was removed",
+            Error::MissingSigningKey(0).to_string()
+        );
+        // Signing with another key works again.
+        signer.set_signing_key(1, *b"fedcba9876543210");
+        assert!(signer.verify(&signer.try_create_from(&mut generator).unwrap()));
+    }
+
+    #[test]
+    fn domain_separation_test() {
+        // Without the MAC length in the message, the 16-bit MAC of an EUID with zero low
+        // bits would be the low half of its 32-bit MAC.
+        let mut short: EuidSigner = EuidSigner::new(0, KEY);
+        short.set_mac_bits(16);
+        let long: EuidSigner = EuidSigner::new(0, KEY);
+        let key: (u64, u64) = long.keys[0].unwrap();
+        let mut rng = rand::thread_rng();
+        let prefixes: usize = (0..1000)
+            .map(|_| EUID(rng.gen(), rng.gen::<u64>() & !0xffff_ffff))
+            .filter(|euid| short.mac(key, euid) == long.mac(key, euid) & 0xffff)
+            .count();
+        assert!(prefixes < 5);
+    }
+
+    #[test]
+    fn drop_test() {
+        let mut signer = core::mem::ManuallyDrop::new(EuidSigner::new(2, KEY));
+        assert!(signer.keys[2].is_some());
+        unsafe { core::mem::ManuallyDrop::drop(&mut signer) };
+        assert!(signer.keys.iter().all(Option::is_none));
+    }
+}
@@ -0,0 +1,107 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+/// SipHash-2-4 (Aumasson and Bernstein) of `data` with the 128-bit key `(k0, k1)`.
+pub fn siphash24(k0: u64, k1: u64, data: &[u8]) -> u64 {
+    let mut v: [u64; 4] = [
+        k0 ^ 0x736f6d6570736575,
+        k1 ^ 0x646f72616e646f6d,
+        k0 ^ 0x6c7967656e657261,
+        k1 ^ 0x7465646279746573,
+    ];
+    let mut chunks = data.chunks_exact(8);
+    for chunk in &mut chunks {
+        let mut word: [u8; 8] = [0u8; 8];
+        word.copy_from_slice(chunk);
+        let m: u64 = u64::from_le_bytes(word);
+        v[3] ^= m;
+        round(&mut v);
+        round(&mut v);
+        v[0] ^= m;
+    }
+    let mut last: [u8; 8] = [0u8; 8];
+    last[..chunks.remainder().len()].copy_from_slice(chunks.remainder());
+    last[7] = data.len() as u8;
+    let m: u64 = u64::from_le_bytes(last);
+    v[3] ^= m;
+    round(&mut v);
+    round(&mut v);
+    v[0] ^= m;
+    v[2] ^= 0xff;
+    for _ in 0..4 {
+        round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+/// Split a 128-bit key into its little-endian halves.
+pub fn key_halves(key: &[u8; 16]) -> (u64, u64) {
+    let mut k0: [u8; 8] = [0u8; 8];
+    let mut k1: [u8; 8] = [0u8; 8];
+    k0.copy_from_slice(&key[..8]);
+    k1.copy_from_slice(&key[8..]);
+    (u64::from_le_bytes(k0), u64::from_le_bytes(k1))
+}
+
+/// Overwrite key material in memory about to be freed.
+pub fn wipe<T: Default>(value: &mut T) {
+    // Volatile, so the store to memory about to be freed is not optimized away.
+    unsafe { core::ptr::write_volatile(value, T::default()) };
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+fn round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13);
+    v[1] ^= v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16);
+    v[3] ^= v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21);
+    v[3] ^= v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17);
+    v[1] ^= v[2];
+    v[2] = v[2].rotate_left(32);
+}
+
+#[cfg(test)]
+mod tests {
+
+    /// Test vectors of the reference implementation: key 00..0f, messages 00..n-1.
+    #[test]
+    fn siphash24_test() {
+        let mut key: [u8; 16] = [0u8; 16];
+        for (i, b) in key.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let (k0, k1) = super::key_halves(&key);
+        let message: Vec<u8> = (0..64).collect();
+        assert_eq!(0x726fdb47dd0e0e31, super::siphash24(k0, k1, &message[..0]));
+        assert_eq!(0x74f839c593dc67fd, super::siphash24(k0, k1, &message[..1]));
+        assert_eq!(0x93f5f5799a932462, super::siphash24(k0, k1, &message[..8]));
+        assert_eq!(0xa129ca6149be45e5, super::siphash24(k0, k1, &message[..15]));
+        assert_eq!(0x958a324ceb064572, super::siphash24(k0, k1, &message[..63]));
+    }
+}
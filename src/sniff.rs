@@ -0,0 +1,344 @@
+// MIT License
+//
+// Copyright (c) 2023 Ardika Rommy Sanjaya
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{base32, EuidBuilder, EUID};
+
+/// Identifier format recognized by [`sniff`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IdFormat {
+    /// EUID with verified check-mod, `C8ZM14GR4JXG0MQXVY18S8TJNA6`.
+    Euid,
+    /// EUID without check-mod, `C8ZM14GR4JXG0MQXVY18S8TJNBZ`.
+    EuidWithoutCheckmod,
+    /// ULID, 26 Crockford base32 symbols, `01ARZ3NDEKTSV4RRFFQ69G5FAV`.
+    Ulid,
+    /// UUID in the hyphenated form, `0188bd5a-3b7c-7d2e-9f00-0123456789ab`.
+    UuidHyphenated,
+    /// UUID as 32 hex digits, `0188bd5a3b7c7d2e9f000123456789ab`.
+    UuidCompact,
+    /// KSUID, 27 base62 characters, `0ujtsYcgvSTl8PAuAdqWYSMnLOv`.
+    Ksuid,
+}
+
+/// How sure [`sniff`] is about the format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Confidence {
+    /// Shape matches, but so would many other tokens.
+    Low,
+    /// Shape and value range match.
+    Medium,
+    /// Shape matches and a check-mod, version or plausible timestamp confirms it.
+    High,
+}
+
+/// Result of [`sniff`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Sniffed {
+    /// Detected format.
+    pub format: IdFormat,
+    /// How sure the detection is.
+    pub confidence: Confidence,
+    /// The identifier as EUID, if it converts: EUIDs as is, ULIDs and version 7 UUIDs
+    /// keeping their timestamp (Unix epoch) and as many random bits as fit, other UUIDs
+    /// bit for bit. KSUIDs (160 bits) do not convert.
+    pub euid: Option<EUID>,
+}
+
+/// Largest KSUID, 2^160 - 1.
+const KSUID_MAX: &[u8; 27] = b"aWgEPTl1tmebfsQzFP4bxwgy80V";
+
+/// ULID and UUIDv7 timestamps from 2000 up to 2100 are plausible.
+const PLAUSIBLE_MILLIS: core::ops::Range<u64> = 946684800000..4102444800000;
+
+/// Classify an identifier as EUID, ULID, UUID or KSUID, None if it is neither.
+///
+/// The input must be exactly the identifier, use [`crate::scan`] to find EUIDs in text.
+///
+/// Example:
+/// ```rust
+/// use euid::{Confidence, IdFormat};
+///
+/// let sniffed = euid::sniff("01ARZ3NDEKTSV4RRFFQ69G5FAV").unwrap();
+/// assert_eq!(IdFormat::Ulid, sniffed.format);
+/// assert_eq!(Confidence::High, sniffed.confidence);
+/// assert_eq!(1469922850259, sniffed.euid.unwrap().timestamp());
+///
+/// let sniffed = euid::sniff("0ujtsYcgvSTl8PAuAdqWYSMnLOv").unwrap();
+/// assert_eq!(IdFormat::Ksuid, sniffed.format);
+/// assert_eq!(None, sniffed.euid);
+/// ```
+pub fn sniff(input: &str) -> Option<Sniffed> {
+    let bytes: &[u8] = input.as_bytes();
+    match bytes.len() {
+        27 => sniff_euid(bytes).or_else(|| sniff_ksuid(bytes)),
+        26 => sniff_ulid(bytes),
+        36 => sniff_uuid_hyphenated(bytes),
+        32 => parse_hex(bytes).map(|value| uuid(value, IdFormat::UuidCompact)),
+        _ => None,
+    }
+}
+
+fn sniffed(format: IdFormat, confidence: Confidence, euid: Option<EUID>) -> Sniffed {
+    Sniffed {
+        format,
+        confidence,
+        euid,
+    }
+}
+
+fn is_mixed_case(bytes: &[u8]) -> bool {
+    bytes.iter().any(u8::is_ascii_lowercase) && bytes.iter().any(u8::is_ascii_uppercase)
+}
+
+fn sniff_euid(bytes: &[u8]) -> Option<Sniffed> {
+    let euid: EUID = base32::decode_bytes(bytes).ok()?;
+    let mut symbols: [u8; 27] = [0u8; 27];
+    symbols.copy_from_slice(bytes);
+    if base32::has_checkmod(&symbols) {
+        Some(sniffed(IdFormat::Euid, Confidence::High, Some(euid)))
+    } else if is_mixed_case(bytes) {
+        // Encoders use a single case, more likely a KSUID.
+        None
+    } else {
+        let format: IdFormat = IdFormat::EuidWithoutCheckmod;
+        Some(sniffed(format, Confidence::Medium, Some(euid)))
+    }
+}
+
+fn sniff_ksuid(bytes: &[u8]) -> Option<Sniffed> {
+    if !bytes.iter().all(u8::is_ascii_alphanumeric) || bytes > &KSUID_MAX[..] {
+        return None;
+    }
+    // Base62 mixes cases, a single case token of 27 characters could be anything.
+    let confidence: Confidence = if is_mixed_case(bytes) {
+        Confidence::High
+    } else {
+        Confidence::Low
+    };
+    Some(sniffed(IdFormat::Ksuid, confidence, None))
+}
+
+fn sniff_ulid(bytes: &[u8]) -> Option<Sniffed> {
+    let mut value: u128 = 0;
+    for (i, &b) in bytes.iter().enumerate() {
+        let symbol: u8 = base32::symbol_value(b)?;
+        // 26 symbols are 130 bits, the first one carries 3.
+        if i == 0 && symbol > 7 {
+            return None;
+        }
+        value = (value << 5) | symbol as u128;
+    }
+    let millis: u64 = (value >> 80) as u64;
+    let random: u128 = value & ((1 << 80) - 1);
+    let euid: Option<EUID> = time_preserving(millis, random);
+    Some(sniffed(IdFormat::Ulid, plausible(millis), euid))
+}
+
+fn sniff_uuid_hyphenated(bytes: &[u8]) -> Option<Sniffed> {
+    let mut hex: [u8; 32] = [0u8; 32];
+    let mut n: usize = 0;
+    for (i, &b) in bytes.iter().enumerate() {
+        if matches!(i, 8 | 13 | 18 | 23) {
+            if b != b'-' {
+                return None;
+            }
+        } else {
+            hex[n] = b;
+            n += 1;
+        }
+    }
+    let mut sniffed: Sniffed = uuid(parse_hex(&hex)?, IdFormat::UuidHyphenated);
+    // The hyphens are specific enough.
+    sniffed.confidence = sniffed.confidence.max(Confidence::Medium);
+    Some(sniffed)
+}
+
+fn parse_hex(bytes: &[u8]) -> Option<u128> {
+    let mut value: u128 = 0;
+    for &b in bytes {
+        value = (value << 4) | (b as char).to_digit(16)? as u128;
+    }
+    Some(value)
+}
+
+fn uuid(value: u128, format: IdFormat) -> Sniffed {
+    let version: u8 = ((value >> 76) & 0xf) as u8;
+    let rfc_variant: bool = (value >> 62) & 0x3 == 0x2;
+    if version == 7 && rfc_variant {
+        let millis: u64 = (value >> 80) as u64;
+        let rand_a: u128 = (value >> 64) & 0xfff;
+        let rand_b: u128 = value & ((1 << 62) - 1);
+        let euid: Option<EUID> = time_preserving(millis, (rand_a << 62) | rand_b);
+        return sniffed(format, plausible(millis), euid);
+    }
+    let confidence: Confidence = if (1..=8).contains(&version) && rfc_variant {
+        Confidence::High
+    } else {
+        // Any 128-bit hash looks like this.
+        Confidence::Low
+    };
+    sniffed(format, confidence, Some(EUID::from(value)))
+}
+
+fn plausible(millis: u64) -> Confidence {
+    if PLAUSIBLE_MILLIS.contains(&millis) {
+        Confidence::High
+    } else {
+        Confidence::Medium
+    }
+}
+
+/// EUID without extension created at `millis`, with the low 79 bits of `random`
+/// (15 filler and 64 random bits).
+fn time_preserving(millis: u64, random: u128) -> Option<EUID> {
+    EuidBuilder::new()
+        .with_timestamp(millis)
+        .with_filler(((random >> 64) & 0x7fff) as u16)
+        .with_r_hi((random >> 32) as u32)
+        .with_r_lo(random as u32)
+        .build()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{Confidence, IdFormat, Sniffed, EUID};
+    use rand::Rng;
+
+    fn format_of(input: &str) -> Option<(IdFormat, Confidence)> {
+        crate::sniff(input).map(|sniffed| (sniffed.format, sniffed.confidence))
+    }
+
+    #[test]
+    fn sniff_test() {
+        let euid: EUID = "C8ZM14GR4JXG0MQXVY18S8TJNA6".parse().unwrap();
+        assert_eq!(
+            Some(Sniffed {
+                format: IdFormat::Euid,
+                confidence: Confidence::High,
+                euid: Some(euid)
+            }),
+            crate::sniff("C8ZM14GR4JXG0MQXVY18S8TJNA6")
+        );
+        assert_eq!(
+            Some((IdFormat::EuidWithoutCheckmod, Confidence::Medium)),
+            format_of("c8zm14gr4jxg0mqxvy18s8tjnbz")
+        );
+        // Wrong check-mod, but a valid (single case) KSUID.
+        assert_eq!(
+            Some((IdFormat::Ksuid, Confidence::Low)),
+            format_of("C8ZM14GR4JXG0MQXVY18S8TJNA7")
+        );
+        assert_eq!(
+            Some((IdFormat::Ksuid, Confidence::High)),
+            format_of("0ujtsYcgvSTl8PAuAdqWYSMnLOv")
+        );
+        assert_eq!(None, format_of("zUzzzzzzzzzzzzzzzzzzzzzzzzz"));
+
+        assert_eq!(
+            Some((IdFormat::Ulid, Confidence::High)),
+            format_of("01ARZ3NDEKTSV4RRFFQ69G5FAV")
+        );
+        assert_eq!(
+            Some((IdFormat::Ulid, Confidence::Medium)),
+            format_of("7ZZZZZZZZZZZZZZZZZZZZZZZZZ")
+        );
+        assert_eq!(None, format_of("8ZZZZZZZZZZZZZZZZZZZZZZZZZ"));
+        // Beyond the 45-bit timestamp of EUID.
+        assert_eq!(
+            None,
+            crate::sniff("7ZZZZZZZZZZZZZZZZZZZZZZZZZ").unwrap().euid
+        );
+
+        let v4: &str = "f81d4fae-7dec-41d0-a765-00a0c91e6bf6";
+        let sniffed: Sniffed = crate::sniff(v4).unwrap();
+        assert_eq!(IdFormat::UuidHyphenated, sniffed.format);
+        assert_eq!(Confidence::High, sniffed.confidence);
+        assert_eq!(
+            Some(EUID::from(0xf81d4fae7dec41d0a76500a0c91e6bf6u128)),
+            sniffed.euid
+        );
+        assert_eq!(
+            Some((IdFormat::UuidCompact, Confidence::High)),
+            format_of("f81d4fae7dec41d0a76500a0c91e6bf6")
+        );
+        // An MD5 digest has the shape, not the version and variant.
+        assert_eq!(
+            Some((IdFormat::UuidCompact, Confidence::Low)),
+            format_of("d41d8cd98f00b204e9800998ecf8427e")
+        );
+        assert_eq!(None, format_of("f81d4fae-7dec-41d0-a765_00a0c91e6bf6"));
+        assert_eq!(None, format_of("g81d4fae7dec41d0a76500a0c91e6bf6"));
+        assert_eq!(None, format_of(""));
+    }
+
+    #[test]
+    fn sniff_conversion_test() {
+        let symbols: &[u8; 32] = crate::base32::ENCODING_SYMBOLS;
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let millis: u64 = rng.gen_range(1600000000000..1900000000000);
+            let random: u128 = rng.gen::<u128>() & ((1 << 80) - 1);
+            let ulid: u128 = ((millis as u128) << 80) | random;
+            let encoded: String = (0..26)
+                .rev()
+                .map(|i| symbols[((ulid >> (5 * i)) & 0x1f) as usize] as char)
+                .collect();
+            let sniffed: Sniffed = crate::sniff(&encoded).unwrap();
+            assert_eq!(IdFormat::Ulid, sniffed.format);
+            let euid: EUID = sniffed.euid.unwrap();
+            assert_eq!(millis, euid.timestamp());
+            assert_eq!(None, euid.extension());
+            let parts: crate::Parts = euid.parts();
+            assert_eq!((random >> 64) as u16 & 0x7fff, parts.filler);
+            assert_eq!(
+                random as u64,
+                ((parts.r_hi as u64) << 32) | parts.r_lo as u64
+            );
+
+            let uuid: u128 = ((millis as u128) << 80)
+                | (0x7 << 76)
+                | ((rng.gen::<u128>() & 0xfff) << 64)
+                | (0x2 << 62)
+                | (rng.gen::<u128>() & ((1 << 62) - 1));
+            let hyphenated: String = format!(
+                "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+                uuid >> 96,
+                (uuid >> 80) & 0xffff,
+                (uuid >> 64) & 0xffff,
+                (uuid >> 48) & 0xffff,
+                uuid & 0xffffffffffff
+            );
+            let sniffed: Sniffed = crate::sniff(&hyphenated).unwrap();
+            assert_eq!(IdFormat::UuidHyphenated, sniffed.format);
+            assert_eq!(Confidence::High, sniffed.confidence);
+            assert_eq!(millis, sniffed.euid.unwrap().timestamp());
+            let compact: Option<Sniffed> = crate::sniff(&format!("{:032X}", uuid));
+            assert_eq!(sniffed.euid, compact.and_then(|sniffed| sniffed.euid));
+
+            let euid: EUID = EUID::from(rng.gen::<u128>());
+            let sniffed: Sniffed = crate::sniff(&euid.encoded(true)).unwrap();
+            assert_eq!((IdFormat::Euid, Some(euid)), (sniffed.format, sniffed.euid));
+        }
+    }
+}
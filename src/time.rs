@@ -20,6 +20,163 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
+/// Point in time the 45-bit timestamp of an EUID counts from.
+///
+/// EUIDs count milliseconds from the Unix epoch by default, which lasts until
+/// Friday, December 12, 3084 12:41:28.831 PM (UTC). A later epoch moves that
+/// limit by the same amount. The epoch is not stored in the EUID, so every
+/// system that creates or reads EUIDs of one kind must agree on it.
+///
+/// Example:
+/// ```rust
+/// use euid::Epoch;
+///
+/// // Wednesday, January 1, 2020 00:00:00 (UTC).
+/// let epoch: Epoch = Epoch::from_unix_millis(1577836800000);
+/// assert_eq!(1577836800000, epoch.unix_millis());
+/// ```
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Epoch(u64);
+
+impl Epoch {
+    /// Thursday, January 1, 1970 00:00:00 (UTC).
+    pub const UNIX: Epoch = Epoch(0);
+
+    /// Create epoch from milliseconds since the Unix epoch.
+    pub const fn from_unix_millis(millis: u64) -> Epoch {
+        Epoch(millis)
+    }
+
+    /// Create epoch from system time, None returns if the time is before the Unix epoch.
+    #[cfg(feature = "std")]
+    pub fn from_system_time(time: std::time::SystemTime) -> Option<Epoch> {
+        match time.duration_since(std::time::UNIX_EPOCH) {
+            Ok(duration) => Some(Epoch(duration.as_millis() as u64)),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns milliseconds since the Unix epoch.
+    pub const fn unix_millis(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the last millisecond since the Unix epoch an EUID can be created at,
+    /// saturating at `u64::MAX`.
+    pub const fn max_unix_millis(self) -> u64 {
+        self.0.saturating_add(crate::EUID::TIMESTAMP_BITMASK)
+    }
+}
+
+/// Precision of the creation time stored in EUIDs.
+///
+/// With a coarser granularity than [`Granularity::Millisecond`], the timestamp field holds
+/// the start of the bucket (counted from the epoch) plus a random offset within the
+/// bucket, so the exact creation millisecond is not revealed. EUIDs of different buckets
+/// still sort by time, within a bucket they sort by their random offset, unless derived
+/// with `next`.
+///
+/// Example:
+/// ```rust
+/// use euid::Granularity;
+///
+/// assert_eq!(60000, Granularity::Minute.millis());
+/// assert_eq!(1699999980000, Granularity::Minute.truncate(1700000000000));
+/// ```
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Granularity {
+    /// Exact creation millisecond (default).
+    #[default]
+    Millisecond,
+    /// Creation second.
+    Second,
+    /// Creation minute.
+    Minute,
+    /// Creation hour.
+    Hour,
+}
+
+impl Granularity {
+    /// Returns the bucket size in milliseconds.
+    pub const fn millis(self) -> u64 {
+        match self {
+            Granularity::Millisecond => 1,
+            Granularity::Second => 1000,
+            Granularity::Minute => 60 * 1000,
+            Granularity::Hour => 60 * 60 * 1000,
+        }
+    }
+
+    /// Returns the start of the bucket `timestamp` falls in.
+    pub const fn truncate(self, timestamp: u64) -> u64 {
+        timestamp - timestamp % self.millis()
+    }
+}
+
+/// A source of wall-clock time used to stamp EUIDs.
+///
+/// Implement this trait to drive EUID generation from a simulated or otherwise
+/// non-system clock (see [`Generator`](crate::Generator)).
+pub trait Clock {
+    /// Returns the number of milliseconds elapsed since the Unix epoch.
+    fn current_timestamp(&self) -> u64;
+}
+
+/// Default clock backed by the system time.
+#[cfg(feature = "std")]
+#[derive(Default, Copy, Clone, Debug)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn current_timestamp(&self) -> u64 {
+        current_timestamp()
+    }
+}
+
+/// Clock that only moves when told to, for reproducible tests and simulations.
+///
+/// Example:
+/// ```rust
+/// use euid::{Clock, ManualClock};
+///
+/// let clock: ManualClock = ManualClock::new(1700000000000);
+/// clock.advance(5);
+/// assert_eq!(1700000000005, clock.current_timestamp());
+/// ```
+#[derive(Default, Debug)]
+pub struct ManualClock(portable_atomic::AtomicU64);
+
+impl ManualClock {
+    /// Create clock stopped at `timestamp` milliseconds since the Unix epoch.
+    pub fn new(timestamp: u64) -> ManualClock {
+        ManualClock(portable_atomic::AtomicU64::new(timestamp))
+    }
+
+    /// Move the clock to `timestamp` milliseconds since the Unix epoch.
+    pub fn set(&self, timestamp: u64) {
+        self.0.store(timestamp, portable_atomic::Ordering::SeqCst);
+    }
+
+    /// Move the clock forward by `millis` milliseconds.
+    pub fn advance(&self, millis: u64) {
+        self.0.fetch_add(millis, portable_atomic::Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn current_timestamp(&self) -> u64 {
+        self.0.load(portable_atomic::Ordering::SeqCst)
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn current_timestamp(&self) -> u64 {
+        (**self).current_timestamp()
+    }
+}
+
+#[cfg(feature = "std")]
 pub fn current_timestamp() -> u64 {
     let duration: Result<std::time::Duration, std::time::SystemTimeError> =
         std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH);
@@ -32,10 +189,86 @@ pub fn current_timestamp() -> u64 {
 #[cfg(test)]
 mod tests {
 
+    #[cfg(feature = "std")]
     #[test]
     fn current_timestamp_test() {
         let now: u64 = super::current_timestamp();
         assert!(0 < now);
         assert!(now < crate::EUID::TIMESTAMP_BITMASK);
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn epoch_test() {
+        assert_eq!(0, super::Epoch::UNIX.unix_millis());
+        assert_eq!(super::Epoch::UNIX, super::Epoch::default());
+        assert_eq!(
+            crate::EUID::TIMESTAMP_BITMASK,
+            super::Epoch::UNIX.max_unix_millis()
+        );
+        let epoch = super::Epoch::from_unix_millis(1577836800000);
+        assert_eq!(
+            Some(epoch),
+            super::Epoch::from_system_time(
+                std::time::UNIX_EPOCH + std::time::Duration::from_millis(1577836800000)
+            )
+        );
+        assert_eq!(
+            None,
+            super::Epoch::from_system_time(
+                std::time::UNIX_EPOCH - std::time::Duration::from_millis(1)
+            )
+        );
+        assert!(super::Epoch::UNIX < epoch);
+    }
+
+    #[test]
+    fn manual_clock_test() {
+        use super::Clock;
+
+        let clock = super::ManualClock::new(1700000000000);
+        assert_eq!(1700000000000, clock.current_timestamp());
+        clock.advance(2);
+        assert_eq!(1700000000002, clock.current_timestamp());
+        clock.set(1600000000000);
+        assert_eq!(1600000000000, clock.current_timestamp());
+        assert_eq!(0, super::ManualClock::default().current_timestamp());
+    }
+
+    #[test]
+    fn epoch_overflow_test() {
+        let epoch = super::Epoch::from_unix_millis(u64::MAX);
+        assert_eq!(u64::MAX, epoch.max_unix_millis());
+        let euid = crate::EUID::from_parts(crate::Parts {
+            timestamp: crate::EUID::TIMESTAMP_BITMASK,
+            ext_len: 0,
+            extension: 0,
+            filler: 0,
+            r_hi: 0,
+            r_lo: 0,
+        })
+        .unwrap();
+        assert_eq!(u64::MAX, euid.unix_timestamp(epoch));
+        assert_eq!(u64::MAX, crate::EUID::default().unix_timestamp(epoch));
+        assert_eq!(
+            Ok(()),
+            crate::ValidationPolicy::new()
+                .with_epoch(epoch)
+                .with_not_before(0)
+                .with_max_future(0)
+                .validate_at(&euid, &super::ManualClock::new(u64::MAX))
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn system_clock_test() {
+        use super::Clock;
+
+        let before: u64 = super::current_timestamp();
+        let now: u64 = super::SystemClock.current_timestamp();
+        let after: u64 = super::current_timestamp();
+        assert!(before <= now);
+        assert!(now <= after);
+    }
 }